@@ -1,37 +1,199 @@
-use crate::types::{Instance, ParallelSchedule};
-use pathfinding::prelude::{DenseCapacity, EdmondsKarp};
+use crate::energy::{bridge, level_assignment};
+use crate::flow::{EdmondsKarp, FlowNetwork};
+use crate::types::{min_wake_up_cost, Instance, ParallelSchedule};
+use crate::visualize::visualize;
 use std::cmp::max;
+use std::error::Error;
+use std::fmt;
+use tracing::{debug, debug_span, enabled, trace, Level};
+
+/// reasons for pltr to fail on an instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PltrError {
+    /// no schedule on m processors meets all release times and deadlines
+    InfeasibleInstance,
+    /// the job with the given id can never be feasibly scheduled on its own
+    InvalidJob { id: usize, reason: String },
+    /// an invariant of the algorithm was violated, which indicates a bug
+    InvariantViolation(String),
+}
+
+impl fmt::Display for PltrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PltrError::InfeasibleInstance => write!(f, "instance is infeasible"),
+            PltrError::InvalidJob { id, reason } => write!(f, "job {id} is invalid: {reason}"),
+            PltrError::InvariantViolation(reason) => {
+                write!(f, "invariant of pltr violated: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for PltrError {}
+
+/// on/off plan of the processors as decided by pltr, processor i at index i
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PowerPlan {
+    /// for each k (at index k - 1) the processor that runs processor level k,
+    /// as chosen by level_assignment, which energy uses as well
+    pub levels: Vec<usize>,
+    /// maximal intervals [a, b) in which pltr keeps each processor busy
+    pub kept_busy: Vec<Vec<(usize, usize)>>,
+    /// maximal intervals [a, b) in which each processor is on:
+    /// its kept-busy intervals where idle gaps shorter than its bridging threshold are bridged
+    pub on: Vec<Vec<(usize, usize)>>,
+}
+
+impl PowerPlan {
+    /// number of times a processor is turned on
+    pub fn wakeups(&self) -> usize {
+        self.on.iter().map(|intervals| intervals.len()).sum()
+    }
+}
+
+/// output of pltr: the schedule together with the power plan it was derived from
+#[derive(Debug, PartialEq)]
+pub struct PltrSolution {
+    pub schedule: ParallelSchedule,
+    pub power_plan: PowerPlan,
+}
 
 /// Parallel Left-to-Right Algorithm
+/// panics if the instance is infeasible or malformed
 pub fn pltr(instance: &Instance) -> ParallelSchedule {
-    let (mut nw, _matrix) = create_graph(instance);
-    //visualize(&matrix, &nw);
+    pltr_with::<EdmondsKarp>(instance)
+}
+
+/// Parallel Left-to-Right Algorithm, computing maximum flows with the backend N
+/// panics if the instance is infeasible or malformed
+pub fn pltr_with<N: FlowNetwork>(instance: &Instance) -> ParallelSchedule {
+    try_pltr_with::<N>(instance).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Parallel Left-to-Right Algorithm, returning an error instead of panicking
+pub fn try_pltr(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    try_pltr_with::<EdmondsKarp>(instance)
+}
+
+/// Parallel Left-to-Right Algorithm, computing maximum flows with the backend N
+/// and returning an error instead of panicking
+pub fn try_pltr_with<N: FlowNetwork>(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    solve_with::<N>(instance).map(|solution| solution.schedule)
+}
+
+/// Parallel Left-to-Right Algorithm, returning the schedule together with its power plan
+pub fn solve(instance: &Instance) -> Result<PltrSolution, PltrError> {
+    solve_with::<EdmondsKarp>(instance)
+}
+
+/// Parallel Left-to-Right Algorithm, returning the schedule together with its power plan
+/// and computing maximum flows with the backend N
+pub fn solve_with<N: FlowNetwork>(instance: &Instance) -> Result<PltrSolution, PltrError> {
+    let _span = debug_span!(
+        "pltr",
+        n = instance.jobs.len(),
+        m = instance.m,
+        d_max = instance.d_max
+    )
+    .entered();
+    validate(instance)?;
+    let mut nw: N = create_graph(instance);
+    if nw.augment() != instance.p_total as i32 {
+        return Err(PltrError::InfeasibleInstance);
+    }
+    let mut kept_busy: Vec<Vec<(usize, usize)>> = vec![Vec::new(); instance.m];
     for k in (1..instance.m + 1).rev() {
+        let _span = debug_span!("processor", k).entered();
         let mut t = 0;
         while t < instance.d_max {
-            t = keepidle(k, t, &mut nw, instance);
-            //visualize(&matrix, &nw);
+            t = keepidle(k, t, &mut nw, instance)?;
             if t < instance.d_max {
-                t = keepbusy(k, t, &mut nw, instance);
-                //visualize(&matrix, &nw);
+                let from = t;
+                t = keepbusy(k, t, &mut nw, instance)?;
+                push_interval(&mut kept_busy[k - 1], from, t);
             }
         }
     }
-    println!("Finished instance");
-    ParallelSchedule::from_flow(&nw, instance)
+    debug!("finished instance");
+    let schedule =
+        ParallelSchedule::try_from_flow(&nw, instance).map_err(PltrError::InvariantViolation)?;
+    let levels = level_assignment(&schedule, instance);
+    let mut power_plan = PowerPlan {
+        levels: levels.clone(),
+        kept_busy: vec![Vec::new(); instance.m],
+        on: vec![Vec::new(); instance.m],
+    };
+    for (intervals, i) in kept_busy.into_iter().zip(levels) {
+        power_plan.on[i] = bridge(&intervals, instance.processors[i].bridging_threshold());
+        power_plan.kept_busy[i] = intervals;
+    }
+    Ok(PltrSolution {
+        schedule,
+        power_plan,
+    })
+}
+
+/// helper for pltr: appends [from, to) to the intervals, extending the last one if adjacent
+fn push_interval(intervals: &mut Vec<(usize, usize)>, from: usize, to: usize) {
+    match intervals.last_mut() {
+        _ if from == to => {}
+        Some(last) if last.1 == from => last.1 = to,
+        _ => intervals.push((from, to)),
+    }
+}
+
+/// checks that every job fits into its time window, that
+/// the precomputed d_max and p_total match the jobs and that m and q match the processors
+pub fn validate(instance: &Instance) -> Result<(), PltrError> {
+    if instance.processors.len() != instance.m {
+        return Err(PltrError::InvariantViolation(format!(
+            "m is {} but {} processors are given",
+            instance.m,
+            instance.processors.len()
+        )));
+    }
+    let q = min_wake_up_cost(&instance.processors);
+    if q != instance.q {
+        return Err(PltrError::InvariantViolation(format!(
+            "q is {} but the smallest wake-up cost of the processors is {}",
+            instance.q, q
+        )));
+    }
+    for job in instance.jobs.iter() {
+        let reason = if job.r >= job.d {
+            format!("release time {} is not before deadline {}", job.r, job.d)
+        } else if job.p > job.d - job.r {
+            format!(
+                "processing volume {} exceeds its window [{}, {})",
+                job.p, job.r, job.d
+            )
+        } else if job.d > instance.d_max {
+            format!("deadline {} exceeds d_max {}", job.d, instance.d_max)
+        } else {
+            continue;
+        };
+        return Err(PltrError::InvalidJob { id: job.id, reason });
+    }
+    let p_total: usize = instance.jobs.iter().map(|job| job.p).sum();
+    if p_total != instance.p_total {
+        return Err(PltrError::InvariantViolation(format!(
+            "p_total is {} but the jobs have total processing volume {}",
+            instance.p_total, p_total
+        )));
+    }
+    Ok(())
 }
 
 /// helper for pltr
-fn binary_search_maximum<F>(mut predicate: F, a: usize, b: usize) -> Option<usize>
+pub(crate) fn binary_search_maximum<F>(mut predicate: F, a: usize, b: usize) -> Option<usize>
 where
     F: FnMut(usize) -> bool,
 {
     let mut a = a;
     let mut b = b;
     let mut t = a + (b - a) / 2;
-    //let mut t: usize = (b - a) / 2;
     while b - a > 1 {
-        //println!("\t\ta:{}, b:{}, t:{}", a, b, t);
         if predicate(t) {
             a = t;
         } else {
@@ -47,195 +209,177 @@ where
 }
 
 /// helper for pltr
-fn capacity(nw: &DenseCapacity<i32>, v1: usize, v2: usize) -> i32 {
-    nw.flow(v1, v2) + nw.residual_capacity(v1, v2)
+fn max_flow_is_total<N: FlowNetwork>(nw: &mut N, instance: &Instance) -> Result<bool, PltrError> {
+    let max_flow = nw.augment();
+    trace!(max_flow, p_total = instance.p_total, "augmented flow");
+    if max_flow > instance.p_total as i32 {
+        return Err(PltrError::InvariantViolation(format!(
+            "maximum flow {max_flow} exceeds p_total {}",
+            instance.p_total
+        )));
+    }
+    Ok(max_flow == instance.p_total as i32)
 }
 
 /// helper for pltr
-fn keepidle_from_to(
+fn keepidle_from_to<N: FlowNetwork>(
     k: usize,
     from: usize,
     to: usize,
-    nw: &mut DenseCapacity<i32>,
+    nw: &mut N,
     instance: &Instance,
-) -> bool {
+) -> Result<bool, PltrError> {
     for t in from..to {
         let v_t = instance.v_node(t);
-        let l_t = capacity(nw, v_t, instance.omega_node());
+        let l_t = nw.capacity(v_t, instance.omega_node());
         if l_t >= k as i32 {
-            return false;
+            return Ok(false);
+        }
+        let m_t = nw.capacity(v_t, instance.gamma_node()) + l_t;
+        if m_t != k as i32 {
+            return Err(PltrError::InvariantViolation(format!(
+                "processor bound {m_t} in slot {t} differs from processor {k}"
+            )));
         }
-        let m_t = capacity(nw, v_t, instance.gamma_node()) + l_t;
-        assert!(m_t == k as i32);
         nw.set_capacity(v_t, instance.gamma_node(), (k - 1) as i32 - l_t);
-        //println!("set_capacity: v{}({})->gamma: {}", t, v_t, (k-1) as i32 - l_t);
     }
-    let (_, max_flow, _) = nw.augment();
-    //println!("\tmax_flow: {}", max_flow);
-    assert!(max_flow <= instance.p_total as i32);
-    max_flow == instance.p_total as i32
+    max_flow_is_total(nw, instance)
 }
 
-/// helper for pltr
-fn keepidle(k: usize, from: usize, nw: &mut DenseCapacity<i32>, instance: &Instance) -> usize {
-    println!("\tkeepidle processor {k} from {from} on");
-    let can_keepidle = |upto| {
-        let mut my_nw = nw.clone();
-        keepidle_from_to(k, from, upto, &mut my_nw, instance)
+/// helper for pltr: finds the maximal upto such that keep_from_to(k, from, upto) remains
+/// feasible by probing on the network and rolling back, then applies it
+fn keep<N, F>(
+    k: usize,
+    from: usize,
+    nw: &mut N,
+    instance: &Instance,
+    keep_from_to: F,
+) -> Result<usize, PltrError>
+where
+    N: FlowNetwork,
+    F: Fn(usize, usize, usize, &mut N, &Instance) -> Result<bool, PltrError>,
+{
+    let mut error = None;
+    let can_keep = |upto| {
+        let checkpoint = nw.checkpoint();
+        let feasible = keep_from_to(k, from, upto, nw, instance);
+        if enabled!(target: "pltr::network", Level::TRACE) {
+            let dot = visualize(&*nw, instance);
+            trace!(target: "pltr::network", upto, %dot, "probed network");
+        }
+        nw.rollback(checkpoint);
+        trace!(upto, ?feasible, "probed");
+        feasible.unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            false
+        })
     };
-    let upto = binary_search_maximum(can_keepidle, from, instance.d_max + 1)
-        .expect("Bounded instance should have remained feasible");
+    let upto = binary_search_maximum(can_keep, from, instance.d_max + 1);
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let upto = upto.ok_or_else(|| {
+        PltrError::InvariantViolation(format!(
+            "bounded instance became infeasible for processor {k} at slot {from}"
+        ))
+    })?;
+    keep_from_to(k, from, upto, nw, instance)?;
+    Ok(upto)
+}
 
-    keepidle_from_to(k, from, upto, nw, instance);
-    println!("\tkept idle processor {k} from {from} upto {upto}");
-    //println!("{}", ParallelSchedule::from_flow(nw, instance));
-    upto
+/// helper for pltr
+fn keepidle<N: FlowNetwork>(
+    k: usize,
+    from: usize,
+    nw: &mut N,
+    instance: &Instance,
+) -> Result<usize, PltrError> {
+    let _span = debug_span!("keepidle", k, from).entered();
+    let upto = keep(k, from, nw, instance, keepidle_from_to)?;
+    debug!(k, from, upto, "kept processor idle");
+    Ok(upto)
 }
 
 /// helper for pltr
-fn keepbusy_from_to(
+fn keepbusy_from_to<N: FlowNetwork>(
     k: usize,
     from: usize,
     to: usize,
-    nw: &mut DenseCapacity<i32>,
+    nw: &mut N,
     instance: &Instance,
-) -> bool {
+) -> Result<bool, PltrError> {
     let mut total_increase = 0;
     let omega = instance.omega_node();
     let gamma = instance.gamma_node();
     for t in from..to {
         let v_t = instance.v_node(t);
-        let old_l_t = capacity(nw, v_t, omega);
+        let old_l_t = nw.capacity(v_t, omega);
         let new_l_t = max(k as i32, old_l_t);
-        assert!(new_l_t > 0);
         let increase = new_l_t - old_l_t;
         nw.set_capacity(v_t, omega, new_l_t);
-        //println!("set_capacity: v{t}({v_t})->omega: {new_l_t}");
-        let new_gamma_cap = capacity(nw, v_t, gamma) - increase;
-        assert!(new_gamma_cap >= 0);
+        let new_gamma_cap = nw.capacity(v_t, gamma) - increase;
+        if new_gamma_cap < 0 {
+            return Err(PltrError::InvariantViolation(format!(
+                "lower bound {new_l_t} in slot {t} exceeds the processor bound"
+            )));
+        }
         nw.set_capacity(v_t, gamma, new_gamma_cap);
-
-        //println!("set_capacity: v{t}({v_t})->gamma: {new_gamma_cap}");
         total_increase += increase;
     }
-    let new_cap_gamma_omega = capacity(nw, gamma, omega) - total_increase;
+    let new_cap_gamma_omega = nw.capacity(gamma, omega) - total_increase;
     if new_cap_gamma_omega < 0 {
-        return false;
+        return Ok(false);
     }
     nw.set_capacity(gamma, omega, new_cap_gamma_omega);
-    //println!("set_capacity: gamma->omega: {new_cap_gamma_omega}");
-    let (_, max_flow, _) = nw.augment();
-    //println!("\tmax_flow: {}", max_flow);
-    assert!(max_flow <= instance.p_total as i32);
-    max_flow == instance.p_total as i32
+    max_flow_is_total(nw, instance)
 }
 
 /// helper for pltr
-fn keepbusy(k: usize, from: usize, nw: &mut DenseCapacity<i32>, instance: &Instance) -> usize {
-    println!("\tkeepbusy processor {k} from {from} on");
-    let can_keepbusy = |upto| {
-        let mut my_nw = nw.clone();
-        keepbusy_from_to(k, from, upto, &mut my_nw, instance)
-    };
-
-    let upto = binary_search_maximum(can_keepbusy, from, instance.d_max + 1)
-        .expect("Bounded instance should have remained feasible");
-    keepbusy_from_to(k, from, upto, nw, instance);
-    println!("\tkept busy processor {k} from {from} upto {upto}");
-    //println!("{}", ParallelSchedule::from_flow(nw, instance));
-    upto
-}
-
-/// convert a list v representing a n x n matrix into an actual matrix
-/// panics if v.len() is not a square number
-fn vec_to_square_matrix<T>(v: &[T], n: usize) -> Vec<Vec<T>>
-where
-    T: Copy,
-{
-    // TODO: panic if v.len() is not a square number
-    let mut m: Vec<Vec<T>> = Vec::new();
-    for i in 0..n {
-        let mut row: Vec<T> = Vec::new();
-        for j in 0..n {
-            row.push(v[n * i + j]);
-        }
-        m.push(row);
-    }
-    m
+fn keepbusy<N: FlowNetwork>(
+    k: usize,
+    from: usize,
+    nw: &mut N,
+    instance: &Instance,
+) -> Result<usize, PltrError> {
+    let _span = debug_span!("keepbusy", k, from).entered();
+    let upto = keep(k, from, nw, instance, keepbusy_from_to)?;
+    debug!(k, from, upto, "kept processor busy");
+    Ok(upto)
 }
 
 /// creates the maximum-flow network corresponding to the problem instance
-fn create_graph(instance: &Instance) -> (DenseCapacity<i32>, Vec<Vec<i32>>) {
-    let mut adj_matrix: Vec<i32> = Vec::new();
-    // alpha -- alpha
-    adj_matrix.push(0);
+pub(crate) fn create_graph<N: FlowNetwork>(instance: &Instance) -> N {
+    let alpha = instance.alpha_node();
+    let gamma = instance.gamma_node();
+    let omega = instance.omega_node();
+    let mut nw = N::new(omega + 1, alpha, omega);
     // alpha -> u_j
-    for job in instance.jobs.iter() {
-        adj_matrix.push(job.p as i32);
-    }
-    // alpha -- t
-    for _t in 0..instance.d_max {
-        adj_matrix.push(0);
+    for (j, job) in instance.jobs.iter().enumerate() {
+        nw.set_capacity(alpha, instance.u_node(j), job.p as i32);
     }
-    // alpha -- gamma
-    adj_matrix.push(0);
-    // alpha -- omega
-    adj_matrix.push(0);
-
-    // u_j (-> v_t)
-    for job in instance.jobs.iter() {
-        // u_j -- alpha
-        adj_matrix.push(0);
-        // u_j -- u_j2
-        for _job2 in instance.jobs.iter() {
-            adj_matrix.push(0);
-        }
-        // u_j -> v_t
-        for t in 0..instance.d_max {
-            if job.r <= t && t < job.d {
-                adj_matrix.push(1)
-            } else {
-                adj_matrix.push(0);
-            }
-        }
-        adj_matrix.push(0);
-        adj_matrix.push(0);
-    }
-    // v_t (-> gamma, omega)
-    for _t in 0..instance.d_max {
-        adj_matrix.push(0);
-        for _job in instance.jobs.iter() {
-            adj_matrix.push(0);
-        }
-        for _t2 in 0..instance.d_max {
-            adj_matrix.push(0);
+    // u_j -> v_t
+    for (j, job) in instance.jobs.iter().enumerate() {
+        for t in job.r..job.d {
+            nw.set_capacity(instance.u_node(j), instance.v_node(t), 1);
         }
+    }
+    // v_t -> gamma, v_t -> omega
+    for t in 0..instance.d_max {
         let m = instance.m;
         let l = 0;
-        adj_matrix.push(m as i32);
-        adj_matrix.push(l);
-    }
-    // gamma
-    for _node in 0..1 + instance.jobs.len() + instance.d_max + 1 {
-        adj_matrix.push(0);
+        nw.set_capacity(instance.v_node(t), gamma, m as i32);
+        nw.set_capacity(instance.v_node(t), omega, l);
     }
+    // gamma -> omega
     let sum_l_t: i32 = 0;
-    adj_matrix.push((instance.p_total as i32) - sum_l_t);
-    // omega
-    for _node in 0..1 + instance.jobs.len() + instance.d_max + 2 {
-        adj_matrix.push(0);
-    }
-    let alpha = 0;
-    let omega = instance.jobs.len() + instance.d_max + 2;
-    let my_matrix = vec_to_square_matrix(&adj_matrix, omega + 1);
-    let nw = DenseCapacity::from_vec(alpha, omega, adj_matrix);
-    (nw, my_matrix)
+    nw.set_capacity(gamma, omega, (instance.p_total as i32) - sum_l_t);
+    nw
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::types::Job;
+    use crate::types::{jobs_from, Job, Processor};
     #[test]
     fn bs_on_integers() {
         let v: Vec<usize> = (0..10).collect();
@@ -451,8 +595,10 @@ mod test {
             ],
             m: 6,
             q: 1,
+            processors: vec![Processor::homogeneous(1); 6],
             d_max: 18,
             p_total: 10,
+            penalties: Default::default(),
         };
         let _schedule = pltr(&instance);
     }
@@ -524,8 +670,10 @@ mod test {
             ],
             m: 5,
             q: 1,
+            processors: vec![Processor::homogeneous(1); 5],
             d_max: 11,
             p_total: 16,
+            penalties: Default::default(),
         };
         let _schedule = pltr(&instance);
     }
@@ -584,8 +732,10 @@ mod test {
             ],
             m: 5,
             q: 1,
+            processors: vec![Processor::homogeneous(1); 5],
             d_max: 29,
             p_total: 10,
+            penalties: Default::default(),
         };
         let _schedule = pltr(&instance);
     }
@@ -627,9 +777,184 @@ mod test {
             ],
             m: 5,
             q: 1,
+            processors: vec![Processor::homogeneous(1); 5],
             d_max: 9,
             p_total: 7,
+            penalties: Default::default(),
         };
         let _schedule = pltr(&instance);
     }
+
+    #[test]
+    fn flow_backends_agree() {
+        use crate::energy::energy;
+        use crate::flow::{DenseEdmondsKarp, Dinic, PushRelabel};
+        let jobs = jobs_from(&[
+            (2, 3, 1),
+            (3, 5, 1),
+            (4, 7, 1),
+            (4, 8, 2),
+            (5, 9, 2),
+            (8, 9, 1),
+            (3, 9, 3),
+            (8, 11, 1),
+            (4, 11, 2),
+            (6, 11, 2),
+        ]);
+        let instance = Instance::new(jobs, 5, 2);
+        let expected = energy(&pltr(&instance), &instance).unwrap();
+        for schedule in [
+            pltr_with::<DenseEdmondsKarp>(&instance),
+            pltr_with::<Dinic>(&instance),
+            pltr_with::<PushRelabel>(&instance),
+        ] {
+            assert!(schedule.is_valid_for(&instance).is_ok());
+            assert_eq!(energy(&schedule, &instance).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn power_plan_matches_schedule() {
+        use crate::energy::{energy, on_intervals};
+        let jobs: Vec<Job> = (0..10)
+            .map(|i| Job {
+                id: i,
+                r: 2 * i,
+                d: 2 * i + 1,
+                p: 1,
+            })
+            .chain([Job {
+                id: 10,
+                r: 3,
+                d: 9,
+                p: 4,
+            }])
+            .collect();
+        for q in [1, 2, 5] {
+            let instance = Instance::new(jobs.clone(), 3, q);
+            let solution = solve(&instance).unwrap();
+            assert_eq!(
+                solution.power_plan.kept_busy[0],
+                vec![
+                    (0, 1),
+                    (2, 9),
+                    (10, 11),
+                    (12, 13),
+                    (14, 15),
+                    (16, 17),
+                    (18, 19)
+                ]
+            );
+            assert!(solution.power_plan.kept_busy[2].is_empty());
+            for k in 1..instance.m + 1 {
+                assert_eq!(
+                    solution.power_plan.on[k - 1],
+                    on_intervals(&solution.schedule, k, q)
+                );
+            }
+            assert_eq!(
+                solution.power_plan.wakeups(),
+                energy(&solution.schedule, &instance).unwrap().wakeups
+            );
+        }
+    }
+
+    #[test]
+    fn power_plan_opens_cheapest_processor_first() {
+        let jobs: Vec<Job> = (0..4)
+            .map(|i| Job {
+                id: i,
+                r: i,
+                d: i + 1,
+                p: 1,
+            })
+            .chain([Job {
+                id: 4,
+                r: 2,
+                d: 3,
+                p: 1,
+            }])
+            .collect();
+        let expensive = Processor {
+            wake_up_cost: 2,
+            active_power: 5,
+        };
+        let cheap = Processor {
+            wake_up_cost: 4,
+            active_power: 1,
+        };
+        let instance = Instance::with_processors(jobs, vec![expensive, cheap]);
+        assert_eq!(instance.processor_order(), vec![1, 0]);
+        let power_plan = solve(&instance).unwrap().power_plan;
+        assert_eq!(power_plan.kept_busy, vec![vec![(2, 3)], vec![(0, 4)]]);
+        assert_eq!(power_plan.wakeups(), 2);
+    }
+
+    #[test]
+    fn power_plan_agrees_with_energy() {
+        use crate::energy::energy;
+        // opening the processor with the lower active power first would bridge the long gaps
+        let jobs = jobs_from(&[(0, 1, 1), (0, 1, 1), (10, 11, 1), (20, 21, 1)]);
+        let low_active_power = Processor {
+            wake_up_cost: 100,
+            active_power: 1,
+        };
+        let cheap_wake_up = Processor {
+            wake_up_cost: 1,
+            active_power: 2,
+        };
+        let instance = Instance::with_processors(jobs, vec![low_active_power, cheap_wake_up]);
+        let solution = solve(&instance).unwrap();
+        let cost = energy(&solution.schedule, &instance).unwrap();
+        assert_eq!(solution.power_plan.levels, vec![1, 0]);
+        assert_eq!(solution.power_plan.on[0], vec![(0, 1)]);
+        assert_eq!(solution.power_plan.on[1], vec![(0, 1), (10, 11), (20, 21)]);
+        assert_eq!(solution.power_plan.wakeups(), cost.wakeups);
+        assert_eq!(cost.total, 101 + 9);
+    }
+
+    #[test]
+    fn try_pltr_rejects_inconsistent_processors() {
+        let mut instance = Instance::new(jobs_from(&[(0, 2, 1)]), 2, 1);
+        instance.m = 3;
+        assert!(matches!(
+            try_pltr(&instance),
+            Err(PltrError::InvariantViolation(_))
+        ));
+        instance.m = 2;
+        instance.q = 2;
+        assert!(matches!(
+            try_pltr(&instance),
+            Err(PltrError::InvariantViolation(_))
+        ));
+    }
+
+    #[test]
+    fn try_pltr_rejects_invalid_job() {
+        let jobs = vec![Job {
+            id: 7,
+            r: 3,
+            d: 5,
+            p: 3,
+        }];
+        let instance = Instance::new(jobs, 1, 1);
+        assert!(matches!(
+            try_pltr(&instance),
+            Err(PltrError::InvalidJob { id: 7, .. })
+        ));
+    }
+
+    #[test]
+    fn try_pltr_rejects_infeasible_instance() {
+        let jobs: Vec<Job> = (0..3)
+            .map(|i| Job {
+                id: i,
+                r: 0,
+                d: 2,
+                p: 2,
+            })
+            .collect();
+        let instance = Instance::new(jobs, 2, 1);
+        assert_eq!(try_pltr(&instance), Err(PltrError::InfeasibleInstance));
+    }
 }
@@ -0,0 +1,122 @@
+use crate::types::{ParallelSchedule, Schedule};
+use std::collections::HashMap;
+
+/// assignment of the jobs of a parallel schedule to processors
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessorAssignment {
+    /// the timeline of each processor, processor k at index k - 1
+    pub timelines: Vec<Schedule>,
+    /// number of times a job resumes on a different processor than it last ran on
+    pub migrations: usize,
+    /// number of times a job is interrupted before it is completed
+    pub preemptions: usize,
+}
+
+/// Assigns the jobs of each time slot to processors such that processor k is busy
+/// exactly when at least k jobs are scheduled, matching the nested busy structure of pltr.
+/// A job stays on the processor it last ran on whenever that processor is busy in the slot,
+/// jobs that ran in the previous slot taking precedence.
+/// Panics if more than m jobs are scheduled in some time slot, which is_valid_for rejects.
+pub fn assign(schedule: &ParallelSchedule) -> ProcessorAssignment {
+    let slots = schedule.slots();
+    let mut timelines: Vec<Schedule> = vec![Schedule(vec![None; slots.len()]); schedule.m()];
+    let mut last_processor: HashMap<usize, usize> = HashMap::new();
+    let mut migrations = 0;
+    for (t, jobs) in slots.iter().enumerate() {
+        let busy = jobs.len();
+        let mut placed: Vec<Option<usize>> = vec![None; busy];
+        let mut unplaced: Vec<usize> = Vec::new();
+        let ran_before = |job: &usize| t > 0 && slots[t - 1].contains(job);
+        let (continuing, resuming): (Vec<usize>, Vec<usize>) =
+            jobs.iter().copied().partition(ran_before);
+        for job in continuing.into_iter().chain(resuming) {
+            match last_processor.get(&job) {
+                Some(&k) if k < busy && placed[k].is_none() => placed[k] = Some(job),
+                _ => unplaced.push(job),
+            }
+        }
+        let free: Vec<usize> = (0..busy).filter(|&k| placed[k].is_none()).collect();
+        for (job, k) in unplaced.into_iter().zip(free) {
+            placed[k] = Some(job);
+        }
+        for (k, job) in placed.into_iter().enumerate() {
+            let job = job.expect("every busy processor runs a job");
+            if let Some(previous) = last_processor.insert(job, k) {
+                if previous != k {
+                    migrations += 1;
+                }
+            }
+            timelines[k].0[t] = Some(job);
+        }
+    }
+    ProcessorAssignment {
+        timelines,
+        migrations,
+        preemptions: preemptions(slots),
+    }
+}
+
+/// counts for each job the number of maximal runs of consecutive time slots minus one
+fn preemptions(slots: &[Vec<usize>]) -> usize {
+    let mut runs: HashMap<usize, usize> = HashMap::new();
+    for (t, jobs) in slots.iter().enumerate() {
+        for job in jobs.iter() {
+            if t == 0 || !slots[t - 1].contains(job) {
+                *runs.entry(*job).or_insert(0) += 1;
+            }
+        }
+    }
+    runs.values().map(|r| r - 1).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{Instance, Job};
+
+    #[test]
+    fn nested_and_sticky() {
+        let schedule = ParallelSchedule::from_vec(vec![vec![0], vec![1, 0], vec![0, 1], vec![]], 2);
+        let assignment = assign(&schedule);
+        assert_eq!(
+            assignment.timelines,
+            vec![
+                Schedule(vec![Some(0), Some(0), Some(0), None]),
+                Schedule(vec![None, Some(1), Some(1), None]),
+            ]
+        );
+        assert_eq!(assignment.migrations, 0);
+        assert_eq!(assignment.preemptions, 0);
+    }
+
+    #[test]
+    fn counts_migrations_and_preemptions() {
+        let schedule = ParallelSchedule::from_vec(vec![vec![0, 1], vec![1], vec![0]], 2);
+        let assignment = assign(&schedule);
+        assert_eq!(
+            assignment.timelines,
+            vec![
+                Schedule(vec![Some(0), Some(1), Some(0)]),
+                Schedule(vec![Some(1), None, None]),
+            ]
+        );
+        assert_eq!(assignment.migrations, 1);
+        assert_eq!(assignment.preemptions, 1);
+    }
+
+    #[test]
+    fn overfull_slots_are_invalid() {
+        let jobs: Vec<Job> = (0..3)
+            .map(|id| Job {
+                id,
+                ..Job::new(0, 1, 1)
+            })
+            .collect();
+        let instance = Instance::new(jobs, 2, 1);
+        let schedule = ParallelSchedule::from_vec(vec![vec![0, 1, 2]], 2);
+        assert_eq!(
+            schedule.is_valid_for(&instance),
+            Err("3 jobs scheduled in time slot 0 on 2 processors".to_string())
+        );
+    }
+}
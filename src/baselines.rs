@@ -0,0 +1,291 @@
+use crate::algorithm::{create_graph, try_pltr, validate, PltrError};
+use crate::energy::{busy_intervals, energy, min_cost_matching};
+use crate::flow::{EdmondsKarp, FlowNetwork};
+use crate::types::{Instance, Job, ParallelSchedule};
+use std::fmt;
+
+/// Schedule for keeping all m processors on from the first release time to d_max:
+/// every slot runs as many jobs as possible, as long as the remaining jobs stay feasible.
+/// Its energy under that policy is given by always_on_energy.
+pub fn always_on(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    slot_by_slot(instance, |_, available, _| {
+        (0..available + 1).rev().collect()
+    })
+}
+
+/// Earliest-deadline-first schedule that activates processors lazily:
+/// every slot runs the fewest released jobs of earliest deadline that keep the remaining jobs
+/// feasible, deferring work towards the deadlines, and processors are turned off whenever idle.
+/// EDF is not optimal on several processors, so if no set of earliest-deadline jobs keeps the
+/// remaining jobs feasible, the slot runs the jobs a maximum flow for the remaining jobs puts in it.
+/// Its energy under that policy is given by energy_without_bridging.
+pub fn edf_lazy(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    validate(instance)?;
+    let mut remaining: Vec<usize> = instance.jobs.iter().map(|job| job.p).collect();
+    if flow_from(instance, &remaining, 0).is_none() {
+        return Err(PltrError::InfeasibleInstance);
+    }
+    let mut slots: Vec<Vec<usize>> = Vec::with_capacity(instance.d_max);
+    for t in 0..instance.d_max {
+        let mut available: Vec<usize> = (0..instance.jobs.len())
+            .filter(|&j| remaining[j] > 0 && instance.jobs[j].r <= t)
+            .collect();
+        available.sort_by_key(|&j| (instance.jobs[j].d, j));
+        let most = available.len().min(instance.m);
+        let running = (0..most + 1)
+            .map(|busy| available[..busy].to_vec())
+            .find(|running| {
+                let mut after = remaining.clone();
+                for &j in running.iter() {
+                    after[j] -= 1;
+                }
+                flow_from(instance, &after, t + 1).is_some()
+            })
+            .or_else(|| flow_from(instance, &remaining, t).map(|(jobs, _)| jobs))
+            .ok_or_else(|| {
+                PltrError::InvariantViolation(format!("remaining jobs infeasible in slot {t}"))
+            })?;
+        for &j in running.iter() {
+            remaining[j] -= 1;
+        }
+        slots.push(running.iter().map(|&j| instance.jobs[j].id).collect());
+    }
+    Ok(ParallelSchedule::from_vec(slots, instance.m))
+}
+
+/// Maximum flow for the remaining processing volumes of the jobs from time slot `from` on.
+/// Returns the indices of the jobs it runs in slot `from` and its value,
+/// None if the remaining jobs cannot be completed.
+fn flow_from(instance: &Instance, remaining: &[usize], from: usize) -> Option<(Vec<usize>, i32)> {
+    let mut indices: Vec<usize> = Vec::new();
+    let mut jobs: Vec<Job> = Vec::new();
+    for (j, job) in instance.jobs.iter().enumerate() {
+        if remaining[j] == 0 {
+            continue;
+        }
+        if job.d < from + remaining[j] {
+            return None;
+        }
+        indices.push(j);
+        jobs.push(Job {
+            r: job.r.saturating_sub(from),
+            d: job.d - from,
+            p: remaining[j],
+            ..job.clone()
+        });
+    }
+    let remaining_instance = Instance::with_processors(jobs, instance.processors.clone());
+    let mut nw: EdmondsKarp = create_graph(&remaining_instance);
+    let value = nw.augment();
+    if value != remaining_instance.p_total as i32 {
+        return None;
+    }
+    let running = (0..indices.len())
+        .filter(|&i| {
+            remaining_instance.d_max > 0
+                && nw.flow(remaining_instance.u_node(i), remaining_instance.v_node(0)) == 1
+        })
+        .map(|i| indices[i])
+        .collect();
+    Some((running, value))
+}
+
+/// Greedy wake-up schedule: like edf_lazy, every slot runs as few jobs as keep the remaining jobs
+/// feasible, but if possible not fewer than the processors whose idle gap up to the slot is
+/// shorter than their bridging threshold, where processor k is the k-th of
+/// Instance::processor_order.
+/// Those processors stay on anyway, so they are kept busy instead of idle.
+/// Its energy is given by energy, which bridges such gaps.
+pub fn greedy_wake_up(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    let thresholds: Vec<usize> = instance
+        .processor_order()
+        .into_iter()
+        .map(|i| instance.processors[i].bridging_threshold())
+        .collect();
+    slot_by_slot(instance, |t, available, profile| {
+        // processor k is on if it was busy in one of the last slots within its threshold
+        let on = (1..instance.m + 1)
+            .rev()
+            .find(|&k| {
+                profile[t.saturating_sub(thresholds[k - 1])..t]
+                    .iter()
+                    .any(|&busy| busy >= k)
+            })
+            .unwrap_or(0);
+        // falls back to fewer jobs if the jobs in the slot have been completed already
+        let at_least = on.min(available);
+        (at_least..available + 1)
+            .chain((0..at_least).rev())
+            .collect()
+    })
+}
+
+/// Fixes the number of jobs run in each time slot from left to right: the first candidate
+/// number of jobs for which the remaining jobs stay feasible, given the slot, the number of
+/// jobs available in it and the numbers of jobs fixed for the previous slots.
+fn slot_by_slot<F>(instance: &Instance, mut candidates: F) -> Result<ParallelSchedule, PltrError>
+where
+    F: FnMut(usize, usize, &[usize]) -> Vec<usize>,
+{
+    validate(instance)?;
+    let mut profile: Vec<usize> = Vec::with_capacity(instance.d_max);
+    let mut nw: EdmondsKarp = create_graph(instance);
+    if nw.augment() != instance.p_total as i32 {
+        return Err(PltrError::InfeasibleInstance);
+    }
+    for t in 0..instance.d_max {
+        let available = instance
+            .jobs
+            .iter()
+            .filter(|job| job.r <= t && t < job.d)
+            .count()
+            .min(instance.m);
+        let busy = candidates(t, available, &profile)
+            .into_iter()
+            .find(|&busy| {
+                let checkpoint = nw.checkpoint();
+                let feasible = fix_busy(&mut nw, instance, t, busy);
+                nw.rollback(checkpoint);
+                feasible
+            })
+            .ok_or_else(|| {
+                PltrError::InvariantViolation(format!("no number of jobs feasible in slot {t}"))
+            })?;
+        fix_busy(&mut nw, instance, t, busy);
+        profile.push(busy);
+    }
+    ParallelSchedule::try_from_flow(&nw, instance).map_err(PltrError::InvariantViolation)
+}
+
+/// sets both bounds on the number of jobs in time slot t to busy,
+/// returns whether all jobs can still be scheduled
+fn fix_busy(nw: &mut EdmondsKarp, instance: &Instance, t: usize, busy: usize) -> bool {
+    let v_t = instance.v_node(t);
+    let gamma = instance.gamma_node();
+    let omega = instance.omega_node();
+    let remaining_lower = nw.capacity(gamma, omega) - busy as i32;
+    if remaining_lower < 0 {
+        return false;
+    }
+    nw.set_capacity(v_t, gamma, 0);
+    nw.set_capacity(v_t, omega, busy as i32);
+    nw.set_capacity(gamma, omega, remaining_lower);
+    nw.augment() == instance.p_total as i32
+}
+
+/// energy of keeping every processor on from the first release time to d_max
+pub fn always_on_energy(instance: &Instance) -> usize {
+    let Some(first_release) = instance.jobs.iter().map(|job| job.r).min() else {
+        return 0;
+    };
+    instance
+        .processors
+        .iter()
+        .map(|p| p.active_power * (instance.d_max - first_release) + p.wake_up_cost)
+        .sum()
+}
+
+/// energy of a schedule if every processor is turned off as soon as it is idle
+pub fn energy_without_bridging(schedule: &ParallelSchedule, instance: &Instance) -> usize {
+    let busy: Vec<Vec<(usize, usize)>> = (1..instance.m + 1)
+        .map(|k| busy_intervals(schedule, k))
+        .collect();
+    let level_cost = |level: usize, i: usize| {
+        let p = &instance.processors[i];
+        let busy_time: usize = busy[level].iter().map(|(a, b)| b - a).sum();
+        p.active_power * busy_time + p.wake_up_cost * busy[level].len()
+    };
+    min_cost_matching(instance.m, level_cost)
+        .into_iter()
+        .enumerate()
+        .map(|(level, i)| level_cost(level, i))
+        .sum()
+}
+
+/// energies of pltr and the baselines on an instance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub pltr: usize,
+    pub always_on: usize,
+    pub edf_lazy: usize,
+    pub greedy_wake_up: usize,
+}
+
+/// runs pltr and each baseline on the instance and evaluates them under their power policy
+pub fn compare(instance: &Instance) -> Result<Comparison, PltrError> {
+    Ok(Comparison {
+        pltr: evaluate(&try_pltr(instance)?, instance)?,
+        always_on: always_on_energy(instance),
+        edf_lazy: energy_without_bridging(&edf_lazy(instance)?, instance),
+        greedy_wake_up: evaluate(&greedy_wake_up(instance)?, instance)?,
+    })
+}
+
+fn evaluate(schedule: &ParallelSchedule, instance: &Instance) -> Result<usize, PltrError> {
+    energy(schedule, instance)
+        .map(|cost| cost.total)
+        .map_err(PltrError::InvariantViolation)
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>10} {:>10} {:>10}",
+            "pltr", "always-on", "edf-lazy", "greedy"
+        )?;
+        write!(
+            f,
+            "{:>10} {:>10} {:>10} {:>10}",
+            self.pltr, self.always_on, self.edf_lazy, self.greedy_wake_up
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{jobs_from, EXAMPLE_JOBS};
+
+    #[test]
+    fn valid_baselines() {
+        let jobs = jobs_from(&EXAMPLE_JOBS);
+        let instance = Instance::new(jobs, 3, 3);
+        for schedule in [
+            always_on(&instance),
+            edf_lazy(&instance),
+            greedy_wake_up(&instance),
+        ] {
+            assert!(schedule.unwrap().is_valid_for(&instance).is_ok());
+        }
+        let comparison = compare(&instance).unwrap();
+        assert_eq!(comparison.pltr, 27);
+        assert_eq!(comparison.always_on, 3 * 20 + 3 * 3);
+        assert!(comparison.edf_lazy >= comparison.pltr);
+        assert_eq!(comparison.greedy_wake_up, 27);
+    }
+
+    #[test]
+    fn edf_runs_earliest_deadlines_first() {
+        // job 1 has to run in slot 2, where job 2 with the earlier deadline runs as well
+        let jobs = jobs_from(&[(0, 4, 1), (2, 3, 1), (2, 3, 1)]);
+        let instance = Instance::new(jobs, 2, 1);
+        let schedule = edf_lazy(&instance).unwrap();
+        assert_eq!(
+            schedule,
+            ParallelSchedule::from_vec(vec![vec![], vec![], vec![1, 2], vec![0]], 2)
+        );
+    }
+
+    #[test]
+    fn infeasible_instances() {
+        let instance = Instance::new(jobs_from(&[(0, 1, 1), (0, 1, 1)]), 1, 1);
+        assert_eq!(always_on(&instance), Err(PltrError::InfeasibleInstance));
+        assert_eq!(edf_lazy(&instance), Err(PltrError::InfeasibleInstance));
+        assert_eq!(
+            greedy_wake_up(&instance),
+            Err(PltrError::InfeasibleInstance)
+        );
+        assert_eq!(compare(&instance), Err(PltrError::InfeasibleInstance));
+    }
+}
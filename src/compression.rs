@@ -0,0 +1,414 @@
+use crate::algorithm::{binary_search_maximum, validate, PltrError};
+use crate::flow::{EdmondsKarp, FlowNetwork};
+use crate::types::{Instance, ParallelSchedule};
+use std::cmp::max;
+
+/// maximal interval [start, end) of time slots in which every slot has the same
+/// upper bound m and lower bound l on the number of busy processors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Segment {
+    start: usize,
+    end: usize,
+    m: usize,
+    l: usize,
+}
+
+impl Segment {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Parallel Left-to-Right Algorithm on a compressed time horizon:
+/// the time slots between consecutive release times and deadlines are merged into
+/// a single node of the flow network whose capacities are scaled by its length.
+/// Nodes are split only where pltr keeps a processor idle or busy, so the size of
+/// the network does not depend on d_max.
+/// panics if the instance is infeasible or malformed
+pub fn pltr_compressed(instance: &Instance) -> ParallelSchedule {
+    pltr_compressed_with::<EdmondsKarp>(instance)
+}
+
+/// compressed Parallel Left-to-Right Algorithm, computing maximum flows with the backend N
+/// panics if the instance is infeasible or malformed
+pub fn pltr_compressed_with<N: FlowNetwork>(instance: &Instance) -> ParallelSchedule {
+    try_pltr_compressed_with::<N>(instance).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// compressed Parallel Left-to-Right Algorithm, returning an error instead of panicking
+pub fn try_pltr_compressed(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    try_pltr_compressed_with::<EdmondsKarp>(instance)
+}
+
+/// compressed Parallel Left-to-Right Algorithm, computing maximum flows with the backend N
+/// and returning an error instead of panicking
+pub fn try_pltr_compressed_with<N: FlowNetwork>(
+    instance: &Instance,
+) -> Result<ParallelSchedule, PltrError> {
+    validate(instance)?;
+    let events = event_points(instance);
+    let mut segments: Vec<Segment> = events
+        .windows(2)
+        .map(|w| Segment {
+            start: w[0],
+            end: w[1],
+            m: instance.m,
+            l: 0,
+        })
+        .collect();
+    let mut nw: N = create_compressed_graph(&segments, instance);
+    if nw.augment() != instance.p_total as i32 {
+        return Err(PltrError::InfeasibleInstance);
+    }
+    for k in (1..instance.m + 1).rev() {
+        let mut t = 0;
+        while t < instance.d_max {
+            t = keep::<N>(k, t, Keep::Idle, &mut segments, instance, &events)?;
+            if t < instance.d_max {
+                t = keep::<N>(k, t, Keep::Busy, &mut segments, instance, &events)?;
+            }
+        }
+    }
+    let mut nw: N = create_compressed_graph(&segments, instance);
+    nw.augment();
+    let schedule = expand(&nw, &segments, instance);
+    schedule.is_valid_for(instance).map_err(|error| {
+        PltrError::InvariantViolation(format!("{error}\nThe invalid schedule:\n{schedule}"))
+    })?;
+    Ok(schedule)
+}
+
+/// sorted and deduplicated release times and deadlines, including 0
+fn event_points(instance: &Instance) -> Vec<usize> {
+    let mut events: Vec<usize> = instance
+        .jobs
+        .iter()
+        .flat_map(|job| [job.r, job.d])
+        .collect();
+    events.push(0);
+    events.sort_unstable();
+    events.dedup();
+    events
+}
+
+/// splits the segment containing t such that a segment starts at t
+fn split_at(segments: &mut Vec<Segment>, t: usize) {
+    if let Some(i) = segments.iter().position(|s| s.start < t && t < s.end) {
+        let right = Segment {
+            start: t,
+            ..segments[i]
+        };
+        segments[i].end = t;
+        segments.insert(i + 1, right);
+    }
+}
+
+/// merges adjacent segments with equal bounds unless they are separated by an event point
+fn merge(segments: &mut Vec<Segment>, events: &[usize]) {
+    let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+    for s in segments.drain(..) {
+        match merged.last_mut() {
+            Some(last)
+                if last.m == s.m && last.l == s.l && events.binary_search(&s.start).is_err() =>
+            {
+                last.end = s.end;
+            }
+            _ => merged.push(s),
+        }
+    }
+    *segments = merged;
+}
+
+/// index of the segment node w_i in the maximum flow network,
+/// where w_node(instance, segments.len()) is a spare node for the second part of a split segment
+fn w_node(instance: &Instance, i: usize) -> usize {
+    1 + instance.jobs.len() + i
+}
+
+/// creates the maximum-flow network for the segments, analogously to create_graph
+fn create_compressed_graph<N: FlowNetwork>(segments: &[Segment], instance: &Instance) -> N {
+    let gamma = w_node(instance, segments.len() + 1);
+    let omega = gamma + 1;
+    let mut nw = N::new(omega + 1, 0, omega);
+    for (j, job) in instance.jobs.iter().enumerate() {
+        nw.set_capacity(0, instance.u_node(j), job.p as i32);
+    }
+    for (i, s) in segments.iter().enumerate() {
+        set_segment(&mut nw, w_node(instance, i), s, segments.len(), instance);
+    }
+    let sum_l: usize = segments.iter().map(|s| s.len() * s.l).sum();
+    nw.set_capacity(gamma, omega, instance.p_total as i32 - sum_l as i32);
+    nw
+}
+
+/// sets the capacities of the edges into and out of the node w representing the segment
+fn set_segment<N: FlowNetwork>(
+    nw: &mut N,
+    w: usize,
+    s: &Segment,
+    segment_count: usize,
+    instance: &Instance,
+) {
+    let gamma = w_node(instance, segment_count + 1);
+    let omega = gamma + 1;
+    for (j, job) in instance.jobs.iter().enumerate() {
+        // segments lie between event points, so they are either inside a window or disjoint
+        if job.r <= s.start && s.end <= job.d {
+            nw.set_capacity(instance.u_node(j), w, s.len() as i32);
+        }
+    }
+    nw.set_capacity(w, gamma, (s.len() * (s.m - s.l)) as i32);
+    nw.set_capacity(w, omega, (s.len() * s.l) as i32);
+}
+
+/// whether pltr keeps processor k idle or busy
+#[derive(Clone, Copy, Debug)]
+enum Keep {
+    Idle,
+    Busy,
+}
+
+impl Keep {
+    /// the segment with the bounds of keeping processor k idle or busy in it,
+    /// None if its lower bound already forces processor k to be busy
+    fn apply(self, k: usize, s: Segment) -> Result<Option<Segment>, PltrError> {
+        match self {
+            Keep::Idle => {
+                if s.l >= k {
+                    return Ok(None);
+                }
+                if s.m != k {
+                    return Err(PltrError::InvariantViolation(format!(
+                        "processor {k} kept idle in [{}, {}) with upper bound {}",
+                        s.start, s.end, s.m
+                    )));
+                }
+                Ok(Some(Segment { m: k - 1, ..s }))
+            }
+            Keep::Busy => {
+                let l = max(k, s.l);
+                if s.m < l {
+                    return Err(PltrError::InvariantViolation(format!(
+                        "processor {k} kept busy in [{}, {}) with upper bound {}",
+                        s.start, s.end, s.m
+                    )));
+                }
+                Ok(Some(Segment { l, ..s }))
+            }
+        }
+    }
+}
+
+/// Whether keeping processor k idle or busy in [from, upto) remains feasible,
+/// where nw holds a maximum flow of value p_total for the segments and from is the start of
+/// segments[first]. A segment containing upto is split, with the spare node taking its second part.
+/// Probes by changing capacities and rolls them back afterwards, so nw is reused by all probes.
+/// Fails if the bounds of a segment contradict each other.
+fn can_keep<N: FlowNetwork>(
+    nw: &mut N,
+    segments: &[Segment],
+    first: usize,
+    upto: usize,
+    k: usize,
+    keep: Keep,
+    instance: &Instance,
+) -> Result<bool, PltrError> {
+    let checkpoint = nw.checkpoint();
+    let mut sum_l: usize = segments.iter().map(|s| s.len() * s.l).sum();
+    let mut feasible = true;
+    for (i, s) in segments.iter().enumerate().skip(first) {
+        if s.start >= upto {
+            break;
+        }
+        let kept = Segment {
+            end: s.end.min(upto),
+            ..*s
+        };
+        let bounded = match keep.apply(k, kept) {
+            Ok(Some(bounded)) => bounded,
+            Ok(None) => {
+                feasible = false;
+                break;
+            }
+            Err(error) => {
+                nw.rollback(checkpoint);
+                return Err(error);
+            }
+        };
+        sum_l = sum_l + bounded.len() * bounded.l - kept.len() * kept.l;
+        set_segment(nw, w_node(instance, i), &bounded, segments.len(), instance);
+        if upto < s.end {
+            let rest = Segment { start: upto, ..*s };
+            let spare = w_node(instance, segments.len());
+            set_segment(nw, spare, &rest, segments.len(), instance);
+        }
+    }
+    if feasible && sum_l <= instance.p_total {
+        let gamma = w_node(instance, segments.len() + 1);
+        nw.set_capacity(gamma, gamma + 1, (instance.p_total - sum_l) as i32);
+        feasible = nw.augment() == instance.p_total as i32;
+    } else {
+        feasible = false;
+    }
+    nw.rollback(checkpoint);
+    Ok(feasible)
+}
+
+/// Finds the maximal upto such that keeping processor k idle or busy in [from, upto)
+/// remains feasible and applies it.
+/// Searches the segment ends first and then the slots of the segment after the last feasible end,
+/// so the number of probes is logarithmic in the number of segments and the length of a segment.
+fn keep<N: FlowNetwork>(
+    k: usize,
+    from: usize,
+    keep: Keep,
+    segments: &mut Vec<Segment>,
+    instance: &Instance,
+    events: &[usize],
+) -> Result<usize, PltrError> {
+    split_at(segments, from);
+    let first = segments
+        .iter()
+        .position(|s| s.start == from)
+        .ok_or_else(|| {
+            PltrError::InvariantViolation(format!("no segment starts at time slot {from}"))
+        })?;
+    let mut nw: N = create_compressed_graph(segments, instance);
+    if nw.augment() != instance.p_total as i32 {
+        return Err(PltrError::InvariantViolation(
+            "bounded instance should have remained feasible".to_string(),
+        ));
+    }
+    // the searches take a predicate, so the first error of a probe is kept aside
+    let mut error: Option<PltrError> = None;
+    let mut probe = |upto| {
+        can_keep(&mut nw, segments, first, upto, k, keep, instance).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            false
+        })
+    };
+    let last_end = binary_search_maximum(|i| probe(segments[i].end), first, segments.len());
+    let next = last_end.map_or(first, |i| i + 1);
+    let upto = match segments.get(next) {
+        Some(s) if s.len() > 1 => {
+            binary_search_maximum(&mut probe, s.start + 1, s.end).unwrap_or(s.start)
+        }
+        Some(s) => s.start,
+        None => instance.d_max,
+    };
+    if let Some(error) = error {
+        return Err(error);
+    }
+    split_at(segments, upto);
+    for s in segments
+        .iter_mut()
+        .filter(|s| from <= s.start && s.end <= upto)
+    {
+        *s = keep.apply(k, *s)?.ok_or_else(|| {
+            PltrError::InvariantViolation(format!(
+                "keeping processor {k} probed feasible in [{from}, {upto})"
+            ))
+        })?;
+    }
+    merge(segments, events);
+    Ok(upto)
+}
+
+/// distributes the flow into each segment over its time slots by McNaughton's wrap-around rule,
+/// which keeps every slot between its lower and upper bound
+fn expand<N: FlowNetwork>(nw: &N, segments: &[Segment], instance: &Instance) -> ParallelSchedule {
+    let mut slots: Vec<Vec<usize>> = vec![Vec::new(); instance.d_max];
+    for (i, s) in segments.iter().enumerate() {
+        let mut position = 0;
+        for (j, job) in instance.jobs.iter().enumerate() {
+            let units = max(nw.flow(instance.u_node(j), w_node(instance, i)), 0) as usize;
+            for _ in 0..units {
+                slots[s.start + position % s.len()].push(job.id);
+                position += 1;
+            }
+        }
+    }
+    ParallelSchedule::from_vec(slots, instance.m)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::{pltr, pltr_with};
+    use crate::energy::energy;
+    use crate::flow::Dinic;
+    use crate::types::Job;
+
+    #[test]
+    fn same_energy_as_pltr() {
+        let jobs: Vec<Job> = [
+            (2, 3, 1),
+            (3, 5, 1),
+            (4, 7, 1),
+            (4, 8, 2),
+            (5, 9, 2),
+            (8, 9, 1),
+        ]
+        .iter()
+        .chain([(3, 9, 3), (8, 11, 1), (4, 11, 2), (6, 11, 2)].iter())
+        .enumerate()
+        .map(|(id, &(r, d, p))| Job { id, r, d, p })
+        .collect();
+        let instance = Instance::new(jobs, 5, 2);
+        let schedule = pltr_compressed(&instance);
+        assert_eq!(
+            energy(&schedule, &instance).unwrap(),
+            energy(&pltr(&instance), &instance).unwrap()
+        );
+    }
+
+    #[test]
+    fn long_horizon() {
+        let jobs: Vec<Job> = (0..4)
+            .map(|i| Job {
+                id: i,
+                r: 200 * i,
+                d: 200 * (i + 2),
+                p: 100 + 20 * i,
+            })
+            .collect();
+        let instance = Instance::new(jobs, 2, 10);
+        let schedule = pltr_compressed(&instance);
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert_eq!(
+            energy(&schedule, &instance).unwrap(),
+            energy(&pltr_with::<Dinic>(&instance), &instance).unwrap()
+        );
+    }
+
+    #[test]
+    fn reports_infeasible_instances() {
+        let jobs: Vec<Job> = (0..3)
+            .map(|id| Job {
+                id,
+                r: 1,
+                d: 3,
+                p: 2,
+            })
+            .collect();
+        let instance = Instance::new(jobs, 2, 1);
+        assert_eq!(
+            try_pltr_compressed(&instance),
+            Err(PltrError::InfeasibleInstance)
+        );
+        let malformed = Instance::new(
+            vec![Job {
+                id: 0,
+                r: 2,
+                d: 1,
+                p: 1,
+            }],
+            1,
+            1,
+        );
+        assert!(matches!(
+            try_pltr_compressed_with::<Dinic>(&malformed),
+            Err(PltrError::InvalidJob { id: 0, .. })
+        ));
+    }
+}
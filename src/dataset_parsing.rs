@@ -1,11 +1,14 @@
-use crate::types::{Job, Instance};
+use crate::types::{Job, Instance, UnrelatedInstance, UnrelatedJob};
 use serde::Deserialize;
 use std::error::Error;
+use std::io::Read;
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 struct CsvJob {
     M: usize,
     N: usize,
+    w: String,
     r: String,
     d: String,
     p: String,
@@ -19,6 +22,9 @@ fn parse_vector_string(s: String) -> Vec<usize> {
         .collect()
 }
 
+/// processing time in the dataset marking a machine the job may not run on
+pub const INELIGIBLE: usize = 1000;
+
 fn parse_matrix_string(s: String, n: usize) -> Vec<usize> {
     let rows: Vec<Vec<usize>> = s.split(';')
         .map(|row_str| parse_vector_string(row_str.to_string()))
@@ -38,7 +44,15 @@ fn parse_matrix_string(s: String, n: usize) -> Vec<usize> {
 }
 
 pub fn parse_csv_to_instance(file_path: &str) -> Result<Vec<Instance>, Box<dyn Error>> {
-    let mut reader = csv::Reader::from_path(file_path)?;
+    instances_from(csv::Reader::from_path(file_path)?)
+}
+
+/// parses the dataset from a reader, e.g. standard input
+pub fn parse_csv_reader_to_instance<R: Read>(reader: R) -> Result<Vec<Instance>, Box<dyn Error>> {
+    instances_from(csv::Reader::from_reader(reader))
+}
+
+fn instances_from<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<Instance>, Box<dyn Error>> {
     let mut instances = Vec::new();
 
     for result in reader.deserialize() {
@@ -54,3 +68,130 @@ pub fn parse_csv_to_instance(file_path: &str) -> Result<Vec<Instance>, Box<dyn E
     }
     Ok(instances)
 }
+
+/// parses the dataset as instances in which jobs may be rejected: the dataset only gives
+/// a weight w per job, which penalty(w, p) turns into the penalty for rejecting a job of
+/// processing volume p, e.g. |w, p| w * p
+pub fn parse_csv_to_instance_with_penalties<F>(file_path: &str, penalty: F) -> Result<Vec<Instance>, Box<dyn Error>>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    instances_with_penalties_from(csv::Reader::from_path(file_path)?, penalty)
+}
+
+/// parses the dataset from a reader as instances in which jobs may be rejected,
+/// see parse_csv_to_instance_with_penalties
+pub fn parse_csv_reader_to_instance_with_penalties<R: Read, F>(reader: R, penalty: F) -> Result<Vec<Instance>, Box<dyn Error>>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    instances_with_penalties_from(csv::Reader::from_reader(reader), penalty)
+}
+
+fn instances_with_penalties_from<R: Read, F>(mut reader: csv::Reader<R>, penalty: F) -> Result<Vec<Instance>, Box<dyn Error>>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    let mut instances = Vec::new();
+
+    for result in reader.deserialize() {
+        let record: CsvJob = result?;
+        let w = parse_vector_string(record.w);
+        let r = parse_vector_string(record.r);
+        let d = parse_vector_string(record.d);
+        let p = parse_matrix_string(record.p, record.N);
+        check_len("weights", w.len(), record.N)?;
+        check_len("release times", r.len(), record.N)?;
+        check_len("deadlines", d.len(), record.N)?;
+
+        let jobs: Vec<Job> = (0..record.N).map(|j| Job::new(r[j], d[j], p[j])).collect();
+        let penalties = jobs.iter().enumerate().map(|(j, job)| (job.id, penalty(w[j], p[j]))).collect();
+        instances.push(Instance::new(jobs, record.M, 1).with_penalties(penalties));
+    }
+    Ok(instances)
+}
+
+fn check_len(what: &str, len: usize, expected: usize) -> Result<(), Box<dyn Error>> {
+    if len != expected {
+        return Err(format!("{} {} given, expected {}", len, what, expected).into());
+    }
+    Ok(())
+}
+
+/// parses the dataset as unrelated-machines instances: row i of the processing time matrix
+/// holds the processing volumes of the jobs on machine i,
+/// fails if the lengths of the vectors and the matrix do not match N and M
+pub fn parse_csv_to_unrelated_instances(file_path: &str) -> Result<Vec<UnrelatedInstance>, Box<dyn Error>> {
+    unrelated_instances_from(csv::Reader::from_path(file_path)?)
+}
+
+/// parses the dataset from a reader as unrelated-machines instances, see parse_csv_to_unrelated_instances
+pub fn parse_csv_reader_to_unrelated_instances<R: Read>(reader: R) -> Result<Vec<UnrelatedInstance>, Box<dyn Error>> {
+    unrelated_instances_from(csv::Reader::from_reader(reader))
+}
+
+fn unrelated_instances_from<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<UnrelatedInstance>, Box<dyn Error>> {
+    let mut instances = Vec::new();
+
+    for result in reader.deserialize() {
+        let record: CsvJob = result?;
+        let r = parse_vector_string(record.r);
+        let d = parse_vector_string(record.d);
+        let rows: Vec<Vec<usize>> = record.p.split(';')
+            .map(|row_str| parse_vector_string(row_str.to_string()))
+            .collect();
+        check_len("release times", r.len(), record.N)?;
+        check_len("deadlines", d.len(), record.N)?;
+        check_len("rows of processing times", rows.len(), record.M)?;
+        for row in rows.iter() {
+            check_len("processing times in a row", row.len(), record.N)?;
+        }
+
+        let jobs = (0..record.N).map(|j| {
+            let p = rows.iter()
+                .map(|row| Some(row[j]).filter(|time| *time != INELIGIBLE))
+                .collect();
+            UnrelatedJob { id: j, r: r[j], d: d[j], p }
+        }).collect();
+        instances.push(UnrelatedInstance::new(jobs, record.M, 1));
+    }
+    Ok(instances)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rejects_mismatched_unrelated_matrix() {
+        let header = "Problem_ID,M,N,w,r,d,p,s,e,instance_name\n";
+        let valid = "1,2,2,\"[1,1]\",\"[0,1]\",\"[3,4]\",\"[1,2];[2,1000]\",,,a\n";
+        let missing_row = "1,2,2,\"[1,1]\",\"[0,1]\",\"[3,4]\",\"[1,2]\",,,a\n";
+        let short_row = "1,2,2,\"[1,1]\",\"[0,1]\",\"[3,4]\",\"[1,2];[2]\",,,a\n";
+        let path = std::env::temp_dir().join(format!("unrelated-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, format!("{header}{valid}")).unwrap();
+        let instances = parse_csv_to_unrelated_instances(path).unwrap();
+        assert_eq!(instances[0].jobs[1].p, vec![Some(2), None]);
+        for malformed in [missing_row, short_row] {
+            fs::write(path, format!("{header}{malformed}")).unwrap();
+            assert!(parse_csv_to_unrelated_instances(path).is_err());
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parses_from_reader() {
+        let csv = "Problem_ID,M,N,w,r,d,p,s,e,instance_name\n\
+                   1,2,2,\"[1,1]\",\"[0,1]\",\"[3,4]\",\"[1,2];[2,1000]\",,,a\n";
+        let instances = parse_csv_reader_to_instance(csv.as_bytes()).unwrap();
+        assert_eq!(instances[0].jobs.len(), 2);
+        assert_eq!(instances[0].jobs[1].p, 2);
+        let unrelated = parse_csv_reader_to_unrelated_instances(csv.as_bytes()).unwrap();
+        assert_eq!(unrelated[0].jobs[0].p, vec![Some(1), Some(2)]);
+        let with_penalties = parse_csv_reader_to_instance_with_penalties(csv.as_bytes(), |w, p| w * p).unwrap();
+        let job = &with_penalties[0].jobs[1];
+        assert_eq!(with_penalties[0].penalties[&job.id], 2);
+    }
+}
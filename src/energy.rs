@@ -0,0 +1,250 @@
+use crate::types::{Instance, ParallelSchedule, Processor};
+use pathfinding::kuhn_munkres::kuhn_munkres_min;
+use pathfinding::matrix::Matrix;
+
+/// energy consumed by a schedule under the wake-up cost model,
+/// split up into its components
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnergyCost {
+    /// number of (processor, time slot) pairs in which a job is processed
+    pub busy_time: usize,
+    /// number of idle (processor, time slot) pairs in which the processor is kept on
+    pub bridged_idle_time: usize,
+    /// number of times a processor is turned on
+    pub wakeups: usize,
+    /// total energy: the active power of every processor for each slot it is on
+    /// plus its wake-up cost for each wake-up
+    pub total: usize,
+}
+
+/// returns the maximal intervals [a, b) in which processor k is busy,
+/// where processor k is busy in slot t if at least k jobs are scheduled in t
+pub fn busy_intervals(schedule: &ParallelSchedule, k: usize) -> Vec<(usize, usize)> {
+    let mut intervals: Vec<(usize, usize)> = Vec::new();
+    let mut start: Option<usize> = None;
+    for (t, jobs) in schedule.slots().iter().enumerate() {
+        match (jobs.len() >= k, start) {
+            (true, None) => start = Some(t),
+            (false, Some(a)) => {
+                intervals.push((a, t));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(a) = start {
+        intervals.push((a, schedule.slots().len()));
+    }
+    intervals
+}
+
+/// returns the maximal intervals [a, b) in which processor k is on,
+/// i.e. its busy intervals where idle gaps shorter than q are bridged
+pub fn on_intervals(schedule: &ParallelSchedule, k: usize, q: usize) -> Vec<(usize, usize)> {
+    bridge(&busy_intervals(schedule, k), q)
+}
+
+/// merges sorted, disjoint busy intervals whose idle gap is shorter than q
+pub fn bridge(busy: &[(usize, usize)], q: usize) -> Vec<(usize, usize)> {
+    let mut intervals: Vec<(usize, usize)> = Vec::new();
+    for &(a, b) in busy {
+        match intervals.last_mut() {
+            // keeping the processor on is cheaper than turning it off and on again
+            Some(last) if a - last.1 < q => last.1 = b,
+            _ => intervals.push((a, b)),
+        }
+    }
+    intervals
+}
+
+/// energy of processor level k, given by its busy intervals, when it is run by the processor:
+/// returns the number of slots it is on, its number of wake-ups and the total energy
+fn level_cost(busy: &[(usize, usize)], processor: &Processor) -> (usize, usize, usize) {
+    let on = bridge(busy, processor.bridging_threshold());
+    let on_time: usize = on.iter().map(|(a, b)| b - a).sum();
+    let total = processor.active_power * on_time + processor.wake_up_cost * on.len();
+    (on_time, on.len(), total)
+}
+
+/// Returns for each k (at index k - 1) the index of the processor that runs processor level k,
+/// chosen as a minimum-cost perfect matching between levels and processors.
+/// Levels are independent in the wake-up cost model, so this minimizes the total energy.
+pub fn level_assignment(schedule: &ParallelSchedule, instance: &Instance) -> Vec<usize> {
+    let busy: Vec<Vec<(usize, usize)>> = (1..instance.m + 1)
+        .map(|k| busy_intervals(schedule, k))
+        .collect();
+    min_cost_matching(instance.m, |level, i| {
+        level_cost(&busy[level], &instance.processors[i]).2
+    })
+}
+
+/// minimum-cost perfect matching between m processor levels and m processors,
+/// returning the processor of each level
+pub(crate) fn min_cost_matching<F>(m: usize, cost: F) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    if m == 0 {
+        return Vec::new();
+    }
+    let costs = Matrix::from_fn(m, m, |(level, i)| cost(level, i) as i64);
+    kuhn_munkres_min(&costs).1
+}
+
+/// evaluates the energy of a schedule for the given instance:
+/// each processor pays its active power for every slot it is on and its wake-up cost
+/// for every wake-up, where processor levels are assigned to processors by level_assignment
+/// fails if more than m jobs are scheduled in some time slot or the instance does not have
+/// exactly m processors, other than that the schedule need not be valid for the instance
+pub fn energy(schedule: &ParallelSchedule, instance: &Instance) -> Result<EnergyCost, String> {
+    if instance.processors.len() != instance.m {
+        return Err(format!(
+            "instance has {} processors, but m = {}",
+            instance.processors.len(),
+            instance.m
+        ));
+    }
+    if let Some(t) = schedule
+        .slots()
+        .iter()
+        .position(|jobs| jobs.len() > instance.m)
+    {
+        return Err(format!(
+            "{} jobs scheduled in time slot {t}, but only m = {} processors",
+            schedule.slots()[t].len(),
+            instance.m
+        ));
+    }
+    let mut cost = EnergyCost::default();
+    for (level, i) in level_assignment(schedule, instance).into_iter().enumerate() {
+        let busy = busy_intervals(schedule, level + 1);
+        let (on_time, wakeups, total) = level_cost(&busy, &instance.processors[i]);
+        cost.busy_time += busy.iter().map(|(a, b)| b - a).sum::<usize>();
+        cost.bridged_idle_time += on_time;
+        cost.wakeups += wakeups;
+        cost.total += total;
+    }
+    cost.bridged_idle_time -= cost.busy_time;
+    Ok(cost)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::Job;
+
+    fn on_off_instance(q: usize) -> (Instance, ParallelSchedule) {
+        let jobs: Vec<Job> = (0..10)
+            .map(|i| Job {
+                id: i,
+                r: 2 * i,
+                d: 2 * i + 1,
+                p: 1,
+            })
+            .collect();
+        let m = 2;
+        let schedule_vec = (0..19)
+            .map(|tslot| {
+                if tslot % 2 == 0 {
+                    vec![tslot / 2]
+                } else {
+                    vec![]
+                }
+            })
+            .collect();
+        (
+            Instance::new(jobs, m, q),
+            ParallelSchedule::from_vec(schedule_vec, m),
+        )
+    }
+
+    #[test]
+    fn gaps_not_shorter_than_q_are_not_bridged() {
+        let (instance, schedule) = on_off_instance(1);
+        let cost = energy(&schedule, &instance).unwrap();
+        assert_eq!(
+            cost,
+            EnergyCost {
+                busy_time: 10,
+                bridged_idle_time: 0,
+                wakeups: 10,
+                total: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn gaps_shorter_than_q_are_bridged() {
+        let (instance, schedule) = on_off_instance(2);
+        let cost = energy(&schedule, &instance).unwrap();
+        assert_eq!(
+            cost,
+            EnergyCost {
+                busy_time: 10,
+                bridged_idle_time: 9,
+                wakeups: 1,
+                total: 21,
+            }
+        );
+    }
+
+    #[test]
+    fn heterogeneous_processors() {
+        let schedule =
+            ParallelSchedule::from_vec(vec![vec![0, 1], vec![0], vec![], vec![], vec![0, 1]], 2);
+        let jobs: Vec<Job> = (0..2)
+            .map(|id| Job {
+                id,
+                r: 0,
+                d: 5,
+                p: 3 - id,
+            })
+            .collect();
+        let cheap_wake_up = Processor {
+            wake_up_cost: 1,
+            active_power: 3,
+        };
+        let cheap_active = Processor {
+            wake_up_cost: 10,
+            active_power: 1,
+        };
+        let instance = Instance::with_processors(jobs, vec![cheap_wake_up, cheap_active]);
+        // level 1 is on from 0 to 5 on the processor with cheap active power,
+        // level 2 wakes up twice for a single slot on the other one
+        assert_eq!(level_assignment(&schedule, &instance), vec![1, 0]);
+        assert_eq!(
+            energy(&schedule, &instance).unwrap(),
+            EnergyCost {
+                busy_time: 5,
+                bridged_idle_time: 2,
+                wakeups: 3,
+                total: 15 + 8,
+            }
+        );
+    }
+
+    #[test]
+    fn nested_processors() {
+        let schedule =
+            ParallelSchedule::from_vec(vec![vec![0, 1], vec![0], vec![], vec![], vec![0, 1]], 2);
+        assert_eq!(busy_intervals(&schedule, 1), vec![(0, 2), (4, 5)]);
+        assert_eq!(busy_intervals(&schedule, 2), vec![(0, 1), (4, 5)]);
+        assert_eq!(on_intervals(&schedule, 1, 3), vec![(0, 5)]);
+        assert_eq!(on_intervals(&schedule, 2, 3), vec![(0, 1), (4, 5)]);
+    }
+
+    #[test]
+    fn rejects_overfull_slots() {
+        let (instance, _) = on_off_instance(1);
+        let schedule = ParallelSchedule::from_vec(vec![vec![0], vec![1, 2, 3]], 2);
+        let error = energy(&schedule, &instance).unwrap_err();
+        assert!(error.contains("3 jobs scheduled in time slot 1"));
+    }
+
+    #[test]
+    fn rejects_processor_count_mismatch() {
+        let (mut instance, schedule) = on_off_instance(1);
+        instance.processors.pop();
+        assert!(energy(&schedule, &instance).is_err());
+    }
+}
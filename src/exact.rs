@@ -0,0 +1,221 @@
+use crate::algorithm::{create_graph, try_pltr, PltrError};
+use crate::energy::energy;
+use crate::flow::{EdmondsKarp, FlowNetwork};
+use crate::types::{Instance, ParallelSchedule};
+use std::cmp::min;
+
+/// Computes a minimum-energy schedule by branch-and-bound over the number of busy
+/// processors in each time slot.
+/// The maximum-flow network of pltr decides whether a partial profile can still be
+/// completed to a feasible schedule.
+/// The running time is exponential in d_max, so this is only practical for small instances.
+/// On heterogeneous processors, processor k is run by the k-th processor of
+/// Instance::processor_order during the search, so the result is optimal only among
+/// schedules using that order.
+/// Fails like try_pltr if the instance is malformed or infeasible.
+pub fn optimal(instance: &Instance) -> Result<ParallelSchedule, PltrError> {
+    optimal_from(instance, try_pltr(instance)?)
+}
+
+/// ratio between the energy of the pltr schedule and the optimal energy
+pub fn approximation_ratio(instance: &Instance) -> Result<f64, PltrError> {
+    let incumbent = try_pltr(instance)?;
+    let pltr_cost = evaluate(&incumbent, instance)?;
+    let optimal_cost = evaluate(&optimal_from(instance, incumbent)?, instance)?;
+    if optimal_cost == 0 {
+        return Ok(1.0);
+    }
+    Ok(pltr_cost as f64 / optimal_cost as f64)
+}
+
+/// branch-and-bound that starts from the pltr schedule as the incumbent
+fn optimal_from(
+    instance: &Instance,
+    incumbent: ParallelSchedule,
+) -> Result<ParallelSchedule, PltrError> {
+    let nw: EdmondsKarp = create_graph(instance);
+    let mut search = Search {
+        instance,
+        nw,
+        profile: vec![0; instance.d_max],
+        last_busy: vec![None; instance.m + 1],
+        order: instance.processor_order(),
+        min_active_power: instance
+            .processors
+            .iter()
+            .map(|p| p.active_power)
+            .min()
+            .unwrap_or(0),
+        best_cost: evaluate(&incumbent, instance)?,
+        best_profile: None,
+    };
+    search.branch(0, 0, 0);
+    match search.best_profile {
+        None => Ok(incumbent),
+        Some(profile) => {
+            let mut nw = search.nw;
+            for (t, busy) in profile.iter().enumerate() {
+                nw.set_capacity(instance.v_node(t), instance.gamma_node(), *busy as i32);
+            }
+            nw.augment();
+            ParallelSchedule::try_from_flow(&nw, instance).map_err(PltrError::InvariantViolation)
+        }
+    }
+}
+
+fn evaluate(schedule: &ParallelSchedule, instance: &Instance) -> Result<usize, PltrError> {
+    energy(schedule, instance)
+        .map(|cost| cost.total)
+        .map_err(PltrError::InvariantViolation)
+}
+
+/// state of the depth-first branch-and-bound
+struct Search<'a> {
+    instance: &'a Instance,
+    nw: EdmondsKarp,
+    /// number of busy processors in each time slot decided so far
+    profile: Vec<usize>,
+    /// last busy time slot of each processor k
+    last_busy: Vec<Option<usize>>,
+    /// the processor that runs each processor k, at index k - 1
+    order: Vec<usize>,
+    min_active_power: usize,
+    best_cost: usize,
+    best_profile: Option<Vec<usize>>,
+}
+
+impl Search<'_> {
+    /// number of jobs that may be processed in time slot t
+    fn available(&self, t: usize) -> usize {
+        let jobs = self
+            .instance
+            .jobs
+            .iter()
+            .filter(|job| job.r <= t && t < job.d)
+            .count();
+        min(jobs, self.instance.m)
+    }
+
+    /// energy added by turning processor k busy in time slot t,
+    /// bridging the gap since its last busy slot if that is cheaper
+    fn busy_cost(&self, k: usize, t: usize) -> usize {
+        let processor = &self.instance.processors[self.order[k - 1]];
+        let (q, a) = (processor.wake_up_cost, processor.active_power);
+        match self.last_busy[k] {
+            None => q + a,
+            Some(last) => min((t - last - 1) * a, q) + a,
+        }
+    }
+
+    fn is_feasible(&mut self) -> bool {
+        self.nw.augment() == self.instance.p_total as i32
+    }
+
+    fn branch(&mut self, t: usize, cost: usize, capacity: usize) {
+        let remaining = self.instance.p_total.saturating_sub(capacity);
+        if cost + remaining * self.min_active_power >= self.best_cost {
+            return;
+        }
+        if t == self.instance.d_max {
+            self.best_cost = cost;
+            self.best_profile = Some(self.profile.clone());
+            return;
+        }
+        let v_t = self.instance.v_node(t);
+        let gamma = self.instance.gamma_node();
+        for busy in (0..self.available(t) + 1).rev() {
+            self.nw.set_capacity(v_t, gamma, busy as i32);
+            if !self.is_feasible() {
+                // fewer busy processors in t cannot be feasible either
+                break;
+            }
+            let added_cost: usize = (1..busy + 1).map(|k| self.busy_cost(k, t)).sum();
+            let previous: Vec<Option<usize>> = self.last_busy[1..busy + 1].to_vec();
+            for k in 1..busy + 1 {
+                self.last_busy[k] = Some(t);
+            }
+            self.profile[t] = busy;
+            self.branch(t + 1, cost + added_cost, capacity + busy);
+            self.last_busy[1..busy + 1].copy_from_slice(&previous);
+        }
+        self.nw.set_capacity(v_t, gamma, self.instance.m as i32);
+        self.nw.augment();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::pltr;
+    use crate::types::{jobs_from, Job};
+
+    #[test]
+    fn optimal_fills_gap() {
+        let jobs = vec![
+            Job {
+                id: 0,
+                r: 0,
+                d: 1,
+                p: 1,
+            },
+            Job {
+                id: 1,
+                r: 4,
+                d: 5,
+                p: 1,
+            },
+            Job {
+                id: 2,
+                r: 0,
+                d: 5,
+                p: 1,
+            },
+        ];
+        let instance = Instance::new(jobs, 2, 2);
+        let schedule = optimal(&instance).unwrap();
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert_eq!(energy(&schedule, &instance).unwrap().total, 7);
+    }
+
+    #[test]
+    fn optimal_not_worse_than_pltr() {
+        let mut jobs: Vec<Job> = (0..6)
+            .map(|i| Job {
+                id: i,
+                r: i,
+                d: i + 1,
+                p: 1,
+            })
+            .collect();
+        jobs.push(Job {
+            id: 6,
+            r: 1,
+            d: 8,
+            p: 3,
+        });
+        jobs.push(Job {
+            id: 7,
+            r: 3,
+            d: 9,
+            p: 2,
+        });
+        let instance = Instance::new(jobs, 3, 2);
+        let schedule = optimal(&instance).unwrap();
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert!(
+            energy(&schedule, &instance).unwrap().total
+                <= energy(&pltr(&instance), &instance).unwrap().total
+        );
+        assert!(approximation_ratio(&instance).unwrap() >= 1.0);
+    }
+
+    #[test]
+    fn reports_infeasible_instances() {
+        let instance = Instance::new(jobs_from(&[(0, 1, 1), (0, 1, 1)]), 1, 1);
+        assert_eq!(optimal(&instance), Err(PltrError::InfeasibleInstance));
+        assert_eq!(
+            approximation_ratio(&instance),
+            Err(PltrError::InfeasibleInstance)
+        );
+    }
+}
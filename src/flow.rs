@@ -0,0 +1,402 @@
+//! maximum flow backends for the network used by pltr
+use pathfinding::prelude::{DenseCapacity, EdmondsKarp as PathfindingEdmondsKarp};
+use std::collections::HashMap;
+
+mod dinic;
+mod edmonds_karp;
+mod push_relabel;
+
+pub use dinic::Dinic;
+pub use edmonds_karp::EdmondsKarp;
+pub use push_relabel::PushRelabel;
+
+/// Edmonds-Karp on a dense capacity matrix, as provided by pathfinding.
+/// Needs memory quadratic in the number of nodes,
+/// the other backends store only the edges of the network.
+pub type DenseEdmondsKarp = DenseCapacity<i32>;
+
+/// operations on a maximum flow network that pltr relies on
+pub trait FlowNetwork: Clone {
+    /// creates a network with `size` nodes and no edges
+    fn new(size: usize, source: usize, sink: usize) -> Self;
+    /// sets the capacity of the edge from -> to,
+    /// cancelling flow along it if it exceeds the new capacity
+    fn set_capacity(&mut self, from: usize, to: usize, capacity: i32);
+    /// returns the flow on the edge from -> to (negative for flow on to -> from)
+    fn flow(&self, from: usize, to: usize) -> i32;
+    /// returns the residual capacity of the edge from -> to
+    fn residual_capacity(&self, from: usize, to: usize) -> i32;
+    /// augments the current flow to a maximum flow and returns its value
+    fn augment(&mut self) -> i32;
+    /// state of the network that can be restored by rollback
+    type Checkpoint;
+    /// records the current capacities and flows
+    fn checkpoint(&mut self) -> Self::Checkpoint;
+    /// restores the capacities and flows recorded by the checkpoint,
+    /// undoing all changes since then including those of later checkpoints, which are closed too
+    fn rollback(&mut self, checkpoint: Self::Checkpoint);
+    /// keeps all changes since the checkpoint and forgets it,
+    /// so that nothing needs to be recorded for it any more
+    fn release(&mut self, checkpoint: Self::Checkpoint);
+    /// returns the capacity of the edge from -> to
+    fn capacity(&self, from: usize, to: usize) -> i32 {
+        self.flow(from, to) + self.residual_capacity(from, to)
+    }
+}
+
+impl FlowNetwork for DenseCapacity<i32> {
+    fn new(size: usize, source: usize, sink: usize) -> Self {
+        let mut nw = <DenseCapacity<i32> as PathfindingEdmondsKarp<i32>>::new(size, source, sink);
+        nw.omit_details();
+        nw
+    }
+    fn set_capacity(&mut self, from: usize, to: usize, capacity: i32) {
+        PathfindingEdmondsKarp::set_capacity(self, from, to, capacity);
+    }
+    fn flow(&self, from: usize, to: usize) -> i32 {
+        PathfindingEdmondsKarp::flow(self, from, to)
+    }
+    fn residual_capacity(&self, from: usize, to: usize) -> i32 {
+        PathfindingEdmondsKarp::residual_capacity(self, from, to)
+    }
+    fn augment(&mut self) -> i32 {
+        let (_, max_flow, _) = PathfindingEdmondsKarp::augment(self);
+        max_flow
+    }
+    /// the dense matrix has no undo log, so a checkpoint is a full copy
+    type Checkpoint = DenseCapacity<i32>;
+    fn checkpoint(&mut self) -> Self::Checkpoint {
+        self.clone()
+    }
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        *self = checkpoint;
+    }
+    fn release(&mut self, _checkpoint: Self::Checkpoint) {}
+}
+
+#[derive(Clone, Debug)]
+struct Edge {
+    to: usize,
+    capacity: i32,
+    flow: i32,
+}
+
+/// state of the edge pair e, e ^ 1 before a change
+#[derive(Clone, Debug)]
+struct Undo {
+    e: usize,
+    capacity: i32,
+    reverse_capacity: i32,
+    flow: i32,
+}
+
+/// residual graph stored as adjacency lists,
+/// where edge e and its reverse edge e ^ 1 are stored next to each other
+#[derive(Clone, Debug)]
+pub(crate) struct ResidualGraph {
+    source: usize,
+    sink: usize,
+    adjacency: Vec<Vec<usize>>,
+    edges: Vec<Edge>,
+    index: HashMap<(usize, usize), usize>,
+    /// changes to edges since the oldest open checkpoint, which is only recorded
+    /// while a checkpoint is open
+    undo_log: Vec<Undo>,
+    /// position in the undo log of each open checkpoint, where a checkpoint is its depth
+    checkpoints: Vec<usize>,
+}
+
+impl ResidualGraph {
+    fn new(size: usize, source: usize, sink: usize) -> ResidualGraph {
+        assert!(source < size && sink < size);
+        ResidualGraph {
+            source,
+            sink,
+            adjacency: vec![Vec::new(); size],
+            edges: Vec::new(),
+            index: HashMap::new(),
+            undo_log: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+    fn size(&self) -> usize {
+        self.adjacency.len()
+    }
+    fn edge(&self, from: usize, to: usize) -> Option<usize> {
+        self.index.get(&(from, to)).copied()
+    }
+    fn edge_or_insert(&mut self, from: usize, to: usize) -> usize {
+        if let Some(e) = self.edge(from, to) {
+            return e;
+        }
+        let e = self.edges.len();
+        self.edges.push(Edge {
+            to,
+            capacity: 0,
+            flow: 0,
+        });
+        self.edges.push(Edge {
+            to: from,
+            capacity: 0,
+            flow: 0,
+        });
+        self.adjacency[from].push(e);
+        self.adjacency[to].push(e ^ 1);
+        self.index.insert((from, to), e);
+        self.index.insert((to, from), e ^ 1);
+        e
+    }
+    fn residual(&self, e: usize) -> i32 {
+        self.edges[e].capacity - self.edges[e].flow
+    }
+    /// records the state of edge e in the undo log if a checkpoint is open
+    fn record(&mut self, e: usize) {
+        if !self.checkpoints.is_empty() {
+            self.undo_log.push(Undo {
+                e,
+                capacity: self.edges[e].capacity,
+                reverse_capacity: self.edges[e ^ 1].capacity,
+                flow: self.edges[e].flow,
+            });
+        }
+    }
+    fn checkpoint(&mut self) -> usize {
+        self.checkpoints.push(self.undo_log.len());
+        self.checkpoints.len() - 1
+    }
+    /// undoes the changes since the checkpoint and closes it together with all later ones
+    fn rollback(&mut self, checkpoint: usize) {
+        let position = self.checkpoints[checkpoint];
+        while self.undo_log.len() > position {
+            let undo = self.undo_log.pop().unwrap();
+            self.edges[undo.e].capacity = undo.capacity;
+            self.edges[undo.e ^ 1].capacity = undo.reverse_capacity;
+            self.edges[undo.e].flow = undo.flow;
+            self.edges[undo.e ^ 1].flow = -undo.flow;
+        }
+        self.checkpoints.truncate(checkpoint);
+    }
+    /// closes the checkpoint together with all later ones, keeping their changes
+    fn release(&mut self, checkpoint: usize) {
+        debug_assert!(checkpoint < self.checkpoints.len());
+        self.checkpoints.truncate(checkpoint);
+        // the changes are only needed to roll back to checkpoints that are still open
+        if self.checkpoints.is_empty() {
+            self.undo_log.clear();
+        }
+    }
+    fn add_flow(&mut self, e: usize, amount: i32) {
+        self.record(e);
+        self.edges[e].flow += amount;
+        self.edges[e ^ 1].flow -= amount;
+    }
+    /// value of the current flow
+    fn value(&self) -> i32 {
+        self.adjacency[self.source]
+            .iter()
+            .map(|&e| self.edges[e].flow)
+            .sum()
+    }
+    /// Removes `amount` units of flow entering `node` along flow paths from the source if
+    /// `backwards`, otherwise `amount` units of flow leaving `node` along flow paths to the sink.
+    /// Relies on flow conservation at every node other than the source and the sink.
+    fn cancel_flow(&mut self, node: usize, amount: i32, backwards: bool) {
+        let end = if backwards { self.source } else { self.sink };
+        let mut stack: Vec<(usize, i32)> = vec![(node, amount)];
+        while let Some((node, mut amount)) = stack.pop() {
+            if node == end {
+                continue;
+            }
+            for i in 0..self.adjacency[node].len() {
+                if amount == 0 {
+                    break;
+                }
+                let e = self.adjacency[node][i];
+                // the edge carrying flow into node if backwards, out of node otherwise
+                let f = if backwards { e ^ 1 } else { e };
+                let carried = self.edges[f].flow;
+                if carried > 0 {
+                    let delta = carried.min(amount);
+                    self.add_flow(f, -delta);
+                    stack.push((self.edges[e].to, delta));
+                    amount -= delta;
+                }
+            }
+            debug_assert_eq!(amount, 0, "flow conservation violated at node {node}");
+        }
+    }
+    fn set_capacity(&mut self, from: usize, to: usize, capacity: i32) {
+        let e = self.edge_or_insert(from, to);
+        let flow = self.edges[e].flow;
+        if capacity < flow {
+            let to_cancel = flow - capacity;
+            self.add_flow(e, -to_cancel);
+            self.cancel_flow(from, to_cancel, true);
+            self.cancel_flow(to, to_cancel, false);
+        }
+        self.record(e);
+        self.edges[e].capacity = capacity;
+    }
+    fn flow(&self, from: usize, to: usize) -> i32 {
+        self.edge(from, to).map_or(0, |e| self.edges[e].flow)
+    }
+    fn residual_capacity(&self, from: usize, to: usize) -> i32 {
+        self.edge(from, to).map_or(0, |e| self.residual(e))
+    }
+}
+
+/// implements the parts of FlowNetwork that are shared by all backends on a ResidualGraph
+macro_rules! residual_graph_network {
+    ($backend:ident, $augment:ident) => {
+        impl $crate::flow::FlowNetwork for $backend {
+            fn new(size: usize, source: usize, sink: usize) -> Self {
+                $backend($crate::flow::ResidualGraph::new(size, source, sink))
+            }
+            fn set_capacity(&mut self, from: usize, to: usize, capacity: i32) {
+                self.0.set_capacity(from, to, capacity);
+            }
+            fn flow(&self, from: usize, to: usize) -> i32 {
+                self.0.flow(from, to)
+            }
+            fn residual_capacity(&self, from: usize, to: usize) -> i32 {
+                self.0.residual_capacity(from, to)
+            }
+            fn augment(&mut self) -> i32 {
+                self.0.$augment();
+                self.0.value()
+            }
+            type Checkpoint = usize;
+            fn checkpoint(&mut self) -> usize {
+                self.0.checkpoint()
+            }
+            fn rollback(&mut self, checkpoint: usize) {
+                self.0.rollback(checkpoint);
+            }
+            fn release(&mut self, checkpoint: usize) {
+                self.0.release(checkpoint);
+            }
+        }
+    };
+}
+use residual_graph_network;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// two disjoint paths 0 -> 1 -> 3 and 0 -> 2 -> 3 and a cross edge 1 -> 2
+    fn diamond<N: FlowNetwork>() -> N {
+        let mut nw = N::new(4, 0, 3);
+        nw.set_capacity(0, 1, 3);
+        nw.set_capacity(0, 2, 2);
+        nw.set_capacity(1, 2, 1);
+        nw.set_capacity(1, 3, 2);
+        nw.set_capacity(2, 3, 3);
+        nw
+    }
+
+    fn max_flow_and_cancel<N: FlowNetwork>() {
+        let mut nw: N = diamond();
+        assert_eq!(nw.augment(), 5);
+        assert_eq!(nw.capacity(1, 3), 2);
+        nw.set_capacity(2, 3, 1);
+        assert!(nw.flow(2, 3) <= 1);
+        assert_eq!(nw.augment(), 3);
+        nw.set_capacity(2, 3, 3);
+        assert_eq!(nw.augment(), 5);
+        assert_eq!(nw.flow(3, 2), -nw.flow(2, 3));
+    }
+
+    fn rollback<N: FlowNetwork>() {
+        let mut nw: N = diamond();
+        assert_eq!(nw.augment(), 5);
+        let checkpoint = nw.checkpoint();
+        nw.set_capacity(0, 1, 0);
+        nw.set_capacity(2, 3, 0);
+        assert_eq!(nw.augment(), 0);
+        let inner = nw.checkpoint();
+        nw.set_capacity(1, 3, 7);
+        nw.rollback(inner);
+        assert_eq!(nw.capacity(1, 3), 2);
+        nw.rollback(checkpoint);
+        assert_eq!(nw.capacity(0, 1), 3);
+        assert_eq!(nw.capacity(2, 3), 3);
+        assert_eq!(nw.flow(0, 1) + nw.flow(0, 2), 5);
+        assert_eq!(nw.augment(), 5);
+    }
+
+    fn release<N: FlowNetwork>() {
+        let mut nw: N = diamond();
+        let checkpoint = nw.checkpoint();
+        let inner = nw.checkpoint();
+        nw.set_capacity(2, 3, 1);
+        nw.release(inner);
+        nw.set_capacity(0, 1, 1);
+        nw.release(checkpoint);
+        assert_eq!(nw.capacity(2, 3), 1);
+        assert_eq!(nw.capacity(0, 1), 1);
+        assert_eq!(nw.augment(), 2);
+    }
+
+    #[test]
+    fn released_changes_are_not_recorded() {
+        let mut graph = ResidualGraph::new(4, 0, 3);
+        let checkpoint = graph.checkpoint();
+        graph.set_capacity(0, 1, 3);
+        assert!(!graph.undo_log.is_empty());
+        graph.release(checkpoint);
+        assert!(graph.undo_log.is_empty());
+        graph.set_capacity(1, 3, 3);
+        assert!(graph.undo_log.is_empty());
+    }
+
+    #[test]
+    fn rollback_closes_nested_checkpoints() {
+        let mut graph = ResidualGraph::new(4, 0, 3);
+        let checkpoint = graph.checkpoint();
+        graph.set_capacity(0, 1, 3);
+        let _inner = graph.checkpoint();
+        graph.set_capacity(1, 3, 3);
+        graph.rollback(checkpoint);
+        assert!(graph.checkpoints.is_empty());
+        assert!(graph.undo_log.is_empty());
+        graph.set_capacity(0, 1, 2);
+        assert!(graph.undo_log.is_empty());
+        assert_eq!(graph.residual_capacity(1, 3), 0);
+    }
+
+    #[test]
+    fn dense_edmonds_karp() {
+        max_flow_and_cancel::<DenseEdmondsKarp>();
+        rollback::<DenseEdmondsKarp>();
+        release::<DenseEdmondsKarp>();
+    }
+    #[test]
+    fn edmonds_karp() {
+        max_flow_and_cancel::<EdmondsKarp>();
+        rollback::<EdmondsKarp>();
+        release::<EdmondsKarp>();
+    }
+    #[test]
+    fn dinic() {
+        max_flow_and_cancel::<Dinic>();
+        rollback::<Dinic>();
+        release::<Dinic>();
+    }
+    #[test]
+    fn dinic_long_path() {
+        // deeper than the call stack of a recursive search
+        let size = 200_000;
+        let mut nw = Dinic::new(size, 0, size - 1);
+        for node in 0..size - 1 {
+            nw.set_capacity(node, node + 1, 1);
+        }
+        assert_eq!(nw.augment(), 1);
+    }
+    #[test]
+    fn push_relabel() {
+        max_flow_and_cancel::<PushRelabel>();
+        rollback::<PushRelabel>();
+        release::<PushRelabel>();
+    }
+}
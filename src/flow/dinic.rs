@@ -0,0 +1,77 @@
+use super::{residual_graph_network, ResidualGraph};
+use std::collections::VecDeque;
+
+/// Dinic's algorithm: augments along blocking flows in the BFS level graph
+#[derive(Clone, Debug)]
+pub struct Dinic(ResidualGraph);
+
+residual_graph_network!(Dinic, augment_dinic);
+
+impl ResidualGraph {
+    /// BFS distances from the source in the residual graph, None if unreachable
+    fn levels(&self) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.size()];
+        let mut queue = VecDeque::new();
+        level[self.source] = Some(0);
+        queue.push_back(self.source);
+        while let Some(node) = queue.pop_front() {
+            for &e in self.adjacency[node].iter() {
+                let next = self.edges[e].to;
+                if level[next].is_none() && self.residual(e) > 0 {
+                    level[next] = level[node].map(|l| l + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        level
+    }
+
+    /// Pushes flow along one path from the source to the sink in the level graph and returns
+    /// its amount, 0 if there is none left.
+    /// The path is searched depth-first with an explicit stack, so long level graphs cannot
+    /// overflow the call stack; dead ends are skipped for good by advancing next_edge.
+    fn blocking_flow(&mut self, level: &[Option<usize>], next_edge: &mut [usize]) -> i32 {
+        // edges of the current path together with the node each one leaves
+        let mut path: Vec<(usize, usize)> = Vec::new();
+        let mut node = self.source;
+        while node != self.sink {
+            if next_edge[node] == self.adjacency[node].len() {
+                // dead end: retreat and skip the edge that led here
+                let Some((previous, _)) = path.pop() else {
+                    return 0;
+                };
+                node = previous;
+                next_edge[node] += 1;
+                continue;
+            }
+            let e = self.adjacency[node][next_edge[node]];
+            let next = self.edges[e].to;
+            if self.residual(e) > 0 && level[next] == level[node].map(|l| l + 1) {
+                path.push((node, e));
+                node = next;
+            } else {
+                next_edge[node] += 1;
+            }
+        }
+        let pushed = path
+            .iter()
+            .map(|&(_, e)| self.residual(e))
+            .min()
+            .unwrap_or(0);
+        for &(_, e) in path.iter() {
+            self.add_flow(e, pushed);
+        }
+        pushed
+    }
+
+    pub(super) fn augment_dinic(&mut self) {
+        loop {
+            let level = self.levels();
+            if level[self.sink].is_none() {
+                return;
+            }
+            let mut next_edge = vec![0; self.size()];
+            while self.blocking_flow(&level, &mut next_edge) > 0 {}
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use super::{residual_graph_network, ResidualGraph};
+use std::collections::VecDeque;
+
+/// Edmonds-Karp algorithm: augments along shortest paths in the residual graph
+#[derive(Clone, Debug)]
+pub struct EdmondsKarp(ResidualGraph);
+
+residual_graph_network!(EdmondsKarp, augment_edmonds_karp);
+
+impl ResidualGraph {
+    /// BFS from the source, returning the edge by which each node was reached
+    fn shortest_path_tree(&self) -> Vec<Option<usize>> {
+        let mut parent_edge = vec![None; self.size()];
+        let mut queue = VecDeque::new();
+        queue.push_back(self.source);
+        while let Some(node) = queue.pop_front() {
+            for &e in self.adjacency[node].iter() {
+                let next = self.edges[e].to;
+                if next != self.source && parent_edge[next].is_none() && self.residual(e) > 0 {
+                    parent_edge[next] = Some(e);
+                    if next == self.sink {
+                        return parent_edge;
+                    }
+                    queue.push_back(next);
+                }
+            }
+        }
+        parent_edge
+    }
+
+    pub(super) fn augment_edmonds_karp(&mut self) {
+        loop {
+            let parent_edge = self.shortest_path_tree();
+            if parent_edge[self.sink].is_none() {
+                return;
+            }
+            let mut path = Vec::new();
+            let mut node = self.sink;
+            while let Some(e) = parent_edge[node] {
+                path.push(e);
+                node = self.edges[e ^ 1].to;
+            }
+            let bottleneck = path.iter().map(|&e| self.residual(e)).min().unwrap();
+            for e in path {
+                self.add_flow(e, bottleneck);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::{residual_graph_network, ResidualGraph};
+use std::collections::VecDeque;
+
+/// FIFO push-relabel algorithm, starting from exact distance labels to the sink
+#[derive(Clone, Debug)]
+pub struct PushRelabel(ResidualGraph);
+
+residual_graph_network!(PushRelabel, augment_push_relabel);
+
+impl ResidualGraph {
+    /// distance labels: BFS distance to the sink in the residual graph,
+    /// nodes that cannot reach the sink are labelled with the number of nodes
+    fn distance_labels(&self) -> Vec<usize> {
+        let n = self.size();
+        let mut height = vec![n; n];
+        let mut queue = VecDeque::new();
+        height[self.sink] = 0;
+        queue.push_back(self.sink);
+        while let Some(node) = queue.pop_front() {
+            for &e in self.adjacency[node].iter() {
+                let prev = self.edges[e].to;
+                if prev != self.source && height[prev] == n && self.residual(e ^ 1) > 0 {
+                    height[prev] = height[node] + 1;
+                    queue.push_back(prev);
+                }
+            }
+        }
+        height[self.source] = n;
+        height
+    }
+
+    pub(super) fn augment_push_relabel(&mut self) {
+        let n = self.size();
+        let mut height = self.distance_labels();
+        let mut excess = vec![0; n];
+        let mut next_edge = vec![0; n];
+        let mut active = VecDeque::new();
+        for i in 0..self.adjacency[self.source].len() {
+            let e = self.adjacency[self.source][i];
+            let residual = self.residual(e);
+            if residual > 0 {
+                let next = self.edges[e].to;
+                self.add_flow(e, residual);
+                if excess[next] == 0 && next != self.sink {
+                    active.push_back(next);
+                }
+                excess[next] += residual;
+            }
+        }
+        while let Some(node) = active.pop_front() {
+            // discharge node
+            while excess[node] > 0 {
+                if next_edge[node] == self.adjacency[node].len() {
+                    // relabel
+                    height[node] = self.adjacency[node]
+                        .iter()
+                        .filter(|&&e| self.residual(e) > 0)
+                        .map(|&e| height[self.edges[e].to] + 1)
+                        .min()
+                        .expect("node with excess has a residual edge");
+                    next_edge[node] = 0;
+                    continue;
+                }
+                let e = self.adjacency[node][next_edge[node]];
+                let next = self.edges[e].to;
+                let residual = self.residual(e);
+                if residual > 0 && height[node] == height[next] + 1 {
+                    let pushed = residual.min(excess[node]);
+                    self.add_flow(e, pushed);
+                    excess[node] -= pushed;
+                    if excess[next] == 0 && next != self.source && next != self.sink {
+                        active.push_back(next);
+                    }
+                    excess[next] += pushed;
+                } else {
+                    next_edge[node] += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::energy::{bridge, busy_intervals, level_assignment};
+use crate::types::{Instance, ParallelSchedule};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// default width of a time slot in pixels
+pub const DEFAULT_SLOT_WIDTH: usize = 12;
+
+const ROW_HEIGHT: usize = 16;
+const LABEL_WIDTH: usize = 100;
+const AXIS_HEIGHT: usize = 24;
+const MARGIN: usize = 8;
+const LEGEND_ENTRY_WIDTH: usize = 130;
+const LEGEND: [(&str, &str); 5] = [
+    ("#eee", "job window"),
+    ("steelblue", "job runs"),
+    ("seagreen", "processor busy"),
+    ("#fc6", "processor idle but on"),
+    ("red", "wake-up"),
+];
+
+/// Renders the instance and its schedule as a standalone SVG Gantt chart on a time axis with
+/// slot_width pixels per time slot, where a slot width of 0 is drawn as 1.
+/// The upper part shows one row per job with its window and the slots it runs in,
+/// the lower part one row per processor with its busy intervals, the bridged idle gaps in which
+/// it stays on and its wake-ups, where processors run levels as assigned by level_assignment.
+/// Everything is drawn in a viewBox, so the chart can be scaled to any size,
+/// which is at least wide enough for the legend.
+pub fn render_svg(schedule: &ParallelSchedule, instance: &Instance, slot_width: usize) -> String {
+    let slot_width = slot_width.max(1);
+    let horizon = instance.d_max.max(schedule.slots().len());
+    let x = |t: usize| LABEL_WIDTH + t * slot_width;
+    let jobs_top = MARGIN + AXIS_HEIGHT;
+    let processors_top = jobs_top + (instance.jobs.len() + 1) * ROW_HEIGHT;
+    let legend_top = processors_top + (instance.m + 1) * ROW_HEIGHT;
+    let width = x(horizon).max(LABEL_WIDTH + LEGEND.len() * LEGEND_ENTRY_WIDTH) + MARGIN;
+    let height = legend_top + ROW_HEIGHT + MARGIN;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"10\">"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>"
+    );
+
+    // time axis with ticks at least 40 pixels apart
+    let step = tick_step(slot_width);
+    let axis_y = MARGIN + AXIS_HEIGHT - 4;
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{}\" y1=\"{axis_y}\" x2=\"{}\" y2=\"{axis_y}\" stroke=\"black\"/>",
+        x(0),
+        x(horizon)
+    );
+    for t in (0..horizon + 1).step_by(step) {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#ccc\"/>\
+             <text x=\"{0}\" y=\"{3}\" text-anchor=\"middle\">{t}</text>",
+            x(t),
+            axis_y,
+            legend_top - ROW_HEIGHT / 2,
+            axis_y - 6
+        );
+    }
+
+    // jobs: window and the slots the job runs in
+    for (row, job) in instance.jobs.iter().enumerate() {
+        let y = jobs_top + row * ROW_HEIGHT;
+        label(&mut svg, y, &format!("job {} (p = {})", job.id, job.p));
+        rect(
+            &mut svg,
+            (x(job.r), y + 2),
+            (job.d.saturating_sub(job.r) * slot_width, ROW_HEIGHT - 4),
+            "fill=\"#eee\" stroke=\"#999\"",
+            &format!("window [{}, {})", job.r, job.d),
+        );
+        let running: Vec<(usize, usize)> = schedule
+            .slots()
+            .iter()
+            .enumerate()
+            .filter(|(_, jobs)| jobs.contains(&job.id))
+            .map(|(t, _)| (t, t + 1))
+            .collect();
+        for (a, b) in bridge(&running, 1) {
+            rect(
+                &mut svg,
+                (x(a), y + 4),
+                ((b - a) * slot_width, ROW_HEIGHT - 8),
+                "fill=\"steelblue\"",
+                &format!("runs [{a}, {b})"),
+            );
+        }
+    }
+
+    // processors: busy intervals, bridged idle gaps and wake-ups
+    let assignment = level_assignment(schedule, instance);
+    for (level, &i) in assignment.iter().enumerate() {
+        let y = processors_top + level * ROW_HEIGHT;
+        let processor = &instance.processors[i];
+        label(&mut svg, y, &format!("processor {i}"));
+        let busy = busy_intervals(schedule, level + 1);
+        let on = bridge(&busy, processor.bridging_threshold());
+        for &(a, b) in on.iter() {
+            rect(
+                &mut svg,
+                (x(a), y + 2),
+                ((b - a) * slot_width, ROW_HEIGHT - 4),
+                "fill=\"#fc6\" stroke=\"#c90\" stroke-dasharray=\"2,2\"",
+                &format!("on [{a}, {b})"),
+            );
+            let _ = writeln!(
+                svg,
+                "<path class=\"wake-up\" d=\"M{0},{1} l-4,-5 h8 z\" fill=\"red\">\
+                 <title>wake-up at {a}, cost {2}</title></path>",
+                x(a),
+                y + 2,
+                processor.wake_up_cost
+            );
+        }
+        for &(a, b) in busy.iter() {
+            rect(
+                &mut svg,
+                (x(a), y + 2),
+                ((b - a) * slot_width, ROW_HEIGHT - 4),
+                "fill=\"seagreen\"",
+                &format!("busy [{a}, {b})"),
+            );
+        }
+    }
+
+    // legend
+    for (n, (color, text)) in LEGEND.iter().enumerate() {
+        let lx = LABEL_WIDTH + n * LEGEND_ENTRY_WIDTH;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{lx}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{color}\" stroke=\"#999\"/>\
+             <text x=\"{}\" y=\"{}\">{text}</text>",
+            legend_top + 3,
+            lx + 14,
+            legend_top + 12
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// writes the chart of render_svg with the default slot width to a file
+pub fn write_svg<P: AsRef<Path>>(
+    path: P,
+    schedule: &ParallelSchedule,
+    instance: &Instance,
+) -> io::Result<()> {
+    fs::write(path, render_svg(schedule, instance, DEFAULT_SLOT_WIDTH))
+}
+
+/// smallest step of the form 1, 2, 5, 10, 20, 50, ... whose ticks are at least 40 pixels apart
+fn tick_step(slot_width: usize) -> usize {
+    let mut magnitude = 1;
+    loop {
+        for step in [magnitude, 2 * magnitude, 5 * magnitude] {
+            if step * slot_width >= 40 {
+                return step;
+            }
+        }
+        magnitude *= 10;
+    }
+}
+
+fn label(svg: &mut String, y: usize, text: &str) {
+    let _ = writeln!(
+        svg,
+        "<text x=\"{MARGIN}\" y=\"{}\">{text}</text>",
+        y + ROW_HEIGHT - 4
+    );
+}
+
+fn rect(
+    svg: &mut String,
+    (x, y): (usize, usize),
+    (w, h): (usize, usize),
+    style: &str,
+    title: &str,
+) {
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" {style}><title>{title}</title></rect>"
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::pltr;
+    use crate::energy::energy;
+    use crate::types::{jobs_from, EXAMPLE_JOBS};
+
+    #[test]
+    fn draws_jobs_and_wakeups() {
+        let jobs = jobs_from(&EXAMPLE_JOBS);
+        let instance = Instance::new(jobs, 3, 3);
+        let schedule = pltr(&instance);
+        let svg = render_svg(&schedule, &instance, DEFAULT_SLOT_WIDTH);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<title>window").count(), instance.jobs.len());
+        assert_eq!(
+            svg.matches("class=\"wake-up\"").count(),
+            energy(&schedule, &instance).unwrap().wakeups
+        );
+        assert_eq!(tick_step(DEFAULT_SLOT_WIDTH), 5);
+        assert_eq!(tick_step(1), 50);
+    }
+
+    #[test]
+    fn fits_legend_and_zero_slot_width() {
+        let instance = Instance::new(jobs_from(&[(0, 2, 1)]), 1, 1);
+        let schedule = pltr(&instance);
+        let legend_width = LABEL_WIDTH + LEGEND.len() * LEGEND_ENTRY_WIDTH + MARGIN;
+        for slot_width in [0, DEFAULT_SLOT_WIDTH] {
+            let svg = render_svg(&schedule, &instance, slot_width);
+            assert!(svg.contains(&format!("width=\"{legend_width}\"")));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::algorithm::pltr;
+use crate::energy::{busy_intervals, energy};
+use crate::types::{Instance, ParallelSchedule};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+/// pltr followed by the local search of improve
+/// panics if the instance is infeasible or malformed
+pub fn pltr_improved(instance: &Instance) -> ParallelSchedule {
+    improve(&pltr(instance), instance).expect("pltr schedules run at most m jobs per slot")
+}
+
+/// Local search that post-optimises a valid schedule without increasing its energy.
+/// A move evicts a busy interval of processor k: in each of its slots, job units are moved out
+/// until fewer than k jobs remain, so that the interval disappears and processor k may stay off
+/// or bridge a gap instead.
+/// A unit is moved along a chain of slots, where each job unit moves into the next slot of
+/// its window and makes room for the previous one, so that swaps of units enable moves
+/// that are impossible for a single job.
+/// Moves are applied as long as they strictly decrease the energy.
+/// Fails if the energy of the schedule cannot be evaluated, see energy.
+pub fn improve(
+    schedule: &ParallelSchedule,
+    instance: &Instance,
+) -> Result<ParallelSchedule, String> {
+    let windows: HashMap<usize, (usize, usize)> = instance
+        .jobs
+        .iter()
+        .map(|job| (job.id, (job.r, job.d)))
+        .collect();
+    let mut slots: Vec<Vec<usize>> = schedule.slots().to_vec();
+    slots.resize(instance.d_max, Vec::new());
+    let cost = |slots: &[Vec<usize>]| {
+        energy(
+            &ParallelSchedule::from_vec(slots.to_vec(), instance.m),
+            instance,
+        )
+        .map(|cost| cost.total)
+    };
+    let mut best = cost(&slots)?;
+    'search: loop {
+        for k in (1..instance.m + 1).rev() {
+            let current = ParallelSchedule::from_vec(slots.clone(), instance.m);
+            let mut intervals = busy_intervals(&current, k);
+            intervals.sort_by_key(|(a, b)| b - a);
+            for (a, b) in intervals {
+                let Some(candidate) = evict(&slots, a..b, k, &windows, instance.m) else {
+                    continue;
+                };
+                let candidate_cost = cost(&candidate)?;
+                if candidate_cost < best {
+                    best = candidate_cost;
+                    slots = candidate;
+                    continue 'search;
+                }
+            }
+        }
+        break;
+    }
+    Ok(ParallelSchedule::from_vec(slots, instance.m))
+}
+
+/// moves job units out of the interval until fewer than k jobs are scheduled in each of its slots
+fn evict(
+    slots: &[Vec<usize>],
+    interval: Range<usize>,
+    k: usize,
+    windows: &HashMap<usize, (usize, usize)>,
+    m: usize,
+) -> Option<Vec<Vec<usize>>> {
+    let mut slots = slots.to_vec();
+    for s in interval.clone() {
+        while slots[s].len() >= k {
+            move_out(&mut slots, s, &interval, windows, m)?;
+        }
+    }
+    Some(slots)
+}
+
+/// Moves one job unit out of slot s along a chain of slots outside of the interval,
+/// found by breadth-first search, ending in a slot with fewer than m jobs.
+/// Prefers the end slot closest to slots in which its new processor is busy already,
+/// closing a gap between two of them if possible.
+fn move_out(
+    slots: &mut [Vec<usize>],
+    s: usize,
+    interval: &Range<usize>,
+    windows: &HashMap<usize, (usize, usize)>,
+    m: usize,
+) -> Option<()> {
+    // previous slot and moved job of each slot reached
+    let mut previous: Vec<Option<(usize, usize)>> = vec![None; slots.len()];
+    let mut queue: VecDeque<usize> = VecDeque::from([s]);
+    let mut ends: Vec<usize> = Vec::new();
+    while let Some(x) = queue.pop_front() {
+        for &job in slots[x].iter() {
+            let (r, d) = windows[&job];
+            for y in r..d {
+                if interval.contains(&y) || previous[y].is_some() || slots[y].contains(&job) {
+                    continue;
+                }
+                previous[y] = Some((x, job));
+                if slots[y].len() < m {
+                    ends.push(y);
+                } else {
+                    queue.push_back(y);
+                }
+            }
+        }
+    }
+    // distances to the closest slots on the left and right in which the new processor is busy
+    let distances_to_busy = |y: usize| {
+        let level = slots[y].len() + 1;
+        let busy = |z: &usize| !interval.contains(z) && slots[*z].len() >= level;
+        let left = (0..y).rev().find(busy).map_or(usize::MAX, |z| y - z);
+        let right = (y + 1..slots.len())
+            .find(busy)
+            .map_or(usize::MAX, |z| z - y);
+        (left.min(right), left.saturating_add(right))
+    };
+    let mut y = ends
+        .into_iter()
+        .min_by_key(|&y| (distances_to_busy(y), usize::MAX - slots[y].len()))?;
+    while y != s {
+        let (x, job) = previous[y].expect("slots on the chain have a predecessor");
+        slots[x].retain(|&other| other != job);
+        slots[y].push(job);
+        y = x;
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{jobs_from, Job, EXAMPLE_JOBS};
+
+    #[test]
+    fn merges_busy_intervals() {
+        let jobs = vec![
+            Job {
+                id: 0,
+                r: 0,
+                d: 6,
+                p: 1,
+            },
+            Job {
+                id: 1,
+                r: 4,
+                d: 5,
+                p: 1,
+            },
+        ];
+        let instance = Instance::new(jobs, 1, 3);
+        let schedule =
+            ParallelSchedule::from_vec(vec![vec![0], vec![], vec![], vec![], vec![1], vec![]], 1);
+        let improved = improve(&schedule, &instance).unwrap();
+        assert!(improved.is_valid_for(&instance).is_ok());
+        assert_eq!(energy(&schedule, &instance).unwrap().total, 8);
+        assert_eq!(energy(&improved, &instance).unwrap().total, 5);
+    }
+
+    #[test]
+    fn chains_unit_moves() {
+        // job 0 closes the gap before job 2 only if job 1 moves to slot 4 to make room in slot 3
+        let jobs: Vec<Job> = [(0, 4), (3, 5), (5, 6), (2, 3)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(r, d))| Job { id, r, d, p: 1 })
+            .collect();
+        let instance = Instance::new(jobs, 1, 1);
+        let schedule =
+            ParallelSchedule::from_vec(vec![vec![0], vec![], vec![3], vec![1], vec![], vec![2]], 1);
+        let improved = improve(&schedule, &instance).unwrap();
+        assert!(improved.is_valid_for(&instance).is_ok());
+        assert_eq!(energy(&schedule, &instance).unwrap().total, 4 + 3);
+        assert_eq!(
+            improved,
+            ParallelSchedule::from_vec(vec![vec![], vec![], vec![3], vec![0], vec![1], vec![2]], 1)
+        );
+    }
+
+    #[test]
+    fn never_worse_than_pltr() {
+        let jobs = jobs_from(&EXAMPLE_JOBS);
+        let instance = Instance::new(jobs, 3, 3);
+        let schedule = pltr_improved(&instance);
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert!(
+            energy(&schedule, &instance).unwrap().total
+                <= energy(&pltr(&instance), &instance).unwrap().total
+        );
+    }
+}
@@ -1,51 +1,122 @@
-use crate::scheduling;
 use crate::types::{Instance, Job};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::cmp;
-//problem instance generation
-fn generate_random_jobs(n: usize, upto: usize, interval_avg: usize) -> Vec<Job> {
+
+/// how the job windows are spread over the horizon
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Distribution {
+    /// windows anywhere in the horizon
+    Random,
+    /// the horizon is split into `valleys` parts and the jobs are dealt round-robin to them
+    Valley,
+}
+
+/// parameters of the instance generators
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    pub distribution: Distribution,
+    /// number of jobs
+    pub n: usize,
+    /// number of processors
+    pub m: usize,
+    /// wake-up cost
+    pub q: usize,
+    /// time horizon the jobs are released and due in
+    pub horizon: usize,
+    /// average length of the job windows
+    pub window: usize,
+    /// number of valleys, only used by the valley distribution
+    pub valleys: usize,
+}
+
+/// an instance together with the configuration and seed it was generated from,
+/// serialized as the instance document with the additional fields `config` and `seed`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeneratedInstance {
+    #[serde(flatten)]
+    pub instance: Instance,
+    pub config: GeneratorConfig,
+    pub seed: u64,
+}
+
+impl GeneratorConfig {
+    /// Checks that windows of the average length fit into the horizon of each valley.
+    /// Window lengths are drawn below max(2, 2 * window) and must be at least 2 shorter than it.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.distribution == Distribution::Valley && self.valleys == 0 {
+            return Err("the valley distribution needs at least one valley".to_string());
+        }
+        let horizon = self.horizon_per_valley();
+        if horizon < self.window.max(1) * 2 + 1 {
+            return Err(format!(
+                "horizon {horizon} per valley is too short for windows of average length {}",
+                self.window
+            ));
+        }
+        Ok(())
+    }
+
+    fn horizon_per_valley(&self) -> usize {
+        match self.distribution {
+            Distribution::Random => self.horizon,
+            Distribution::Valley => self.horizon / self.valleys.max(1),
+        }
+    }
+}
+
+/// Generates an instance of the configuration from the seed.
+/// The same configuration and seed always yield the same instance
+/// with the same version of the rand crate.
+pub fn generate(config: &GeneratorConfig, seed: u64) -> Result<GeneratedInstance, String> {
+    config.validate()?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut jobs = generate_random_jobs(
+        &mut rng,
+        config.n,
+        config.horizon_per_valley(),
+        config.window,
+    )?;
+    if config.distribution == Distribution::Valley {
+        let valley_size = config.horizon_per_valley();
+        for (i, job) in jobs.iter_mut().enumerate() {
+            let offset = valley_size * (i % config.valleys);
+            job.r += offset;
+            job.d += offset;
+        }
+    }
+    jobs.sort_by_key(|job| job.d);
+    Ok(GeneratedInstance {
+        instance: Instance::new(jobs, config.m, config.q),
+        config: *config,
+        seed,
+    })
+}
+
+//problem instance generation, fails if a window does not fit into the horizon upto
+fn generate_random_jobs<R: Rng>(
+    rng: &mut R,
+    n: usize,
+    upto: usize,
+    interval_avg: usize,
+) -> Result<Vec<Job>, String> {
     let mut jobs: Vec<Job> = Vec::with_capacity(n);
-    let mut rng = rand::thread_rng();
     for i in 0..n {
         let interval_size: usize = rng.gen_range(1..cmp::max(2, 2 * interval_avg));
-        assert!(upto - interval_size > 1);
+        if interval_size + 1 >= upto {
+            return Err(format!(
+                "window of length {interval_size} does not fit into horizon {upto}"
+            ));
+        }
         let r: usize = rng.gen_range(0..upto - interval_size);
         let d: usize = r + interval_size;
         let p: usize = rng.gen_range(1..=cmp::max(1, interval_size / 2));
         let job = Job { id: i, r, d, p };
         jobs.push(job);
     }
-    jobs
-}
-pub fn generate_random_instance(
-    n: usize,
-    upto: usize,
-    interval_avg: usize,
-    q: usize,
-    m: usize,
-) -> Instance {
-    let mut jobs = generate_random_jobs(n, upto, interval_avg);
-    jobs.sort_by(|job1, job2| job1.d.cmp(&job2.d));
-    Instance::new(jobs, m, q)
-}
-
-pub fn generate_valley_instance(
-    n: usize,
-    upto: usize,
-    interval_avg: usize,
-    q: usize,
-    m: usize,
-    valley_num: usize,
-) -> Instance {
-    let valley_size = upto / valley_num;
-    let mut jobs = generate_random_jobs(n, valley_size, interval_avg);
-    for (i, job) in jobs.iter_mut().enumerate() {
-        let offset = valley_size * (i % valley_num);
-        job.r += offset;
-        job.d += offset;
-    }
-    jobs.sort_by(|job1, job2| job1.d.cmp(&job2.d));
-    Instance::new(jobs, m, q)
+    Ok(jobs)
 }
 
 pub fn generate_small_deterministic_problem_instance() -> Instance {
@@ -75,14 +146,53 @@ pub fn generate_small_deterministic_problem_instance() -> Instance {
             p: 2,
         },
     ];
-    jobs.sort_by(|job1, job2| job1.d.cmp(&job2.d));
-    let d_max = scheduling::get_d_max(&jobs) + 1;
-    let p_total = scheduling::get_total_processing_volume(&jobs);
-    Instance {
-        jobs,
-        q: 1,
-        m: 1,
-        d_max,
-        p_total,
+    jobs.sort_by_key(|job| job.d);
+    Instance::new(jobs, 1, 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reproducible_from_seed() {
+        let config = GeneratorConfig {
+            distribution: Distribution::Valley,
+            n: 30,
+            m: 2,
+            q: 3,
+            horizon: 100,
+            window: 5,
+            valleys: 4,
+        };
+        let generated = generate(&config, 42).unwrap();
+        let again = generate(&config, 42).unwrap();
+        assert_eq!(format!("{again:?}"), format!("{generated:?}"));
+        assert_ne!(
+            format!("{:?}", generate(&config, 43).unwrap().instance),
+            format!("{:?}", generated.instance)
+        );
+        assert!(generated
+            .instance
+            .jobs
+            .iter()
+            .all(|job| job.r / 25 == (job.d - 1) / 25));
+
+        let serialized = serde_json::to_string(&generated).unwrap();
+        let deserialized: GeneratedInstance = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.config, config);
+        assert_eq!(deserialized.seed, 42);
+        // the document can be read as a plain instance as well
+        let instance: Instance = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(format!("{instance:?}"), format!("{:?}", generated.instance));
+
+        let too_short = GeneratorConfig {
+            horizon: 40,
+            ..config
+        };
+        assert!(generate(&too_short, 42).is_err());
+        // windows that do not fit into the horizon are an error rather than an underflow
+        let mut rng = StdRng::seed_from_u64(42);
+        assert!(generate_random_jobs(&mut rng, 5, 2, 5).is_err());
     }
 }
@@ -0,0 +1,210 @@
+use crate::types::{min_wake_up_cost, Instance, Job, ParallelSchedule, Processor};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version of the JSON documents that instances and schedules are read from and written to.
+///
+/// An instance document has the fields
+/// - `version`: the schema version, currently 1
+/// - `m`: the number of processors
+/// - `q`: the wake-up cost of identical processors with unit active power,
+///   optional if `processors` is given, in which case it must be the smallest wake-up cost
+/// - `processors`: optional list of `m` processors `{"wake_up_cost": .., "active_power": ..}`
+/// - `jobs`: list of jobs `{"id": .., "r": .., "d": .., "p": .., "penalty": ..}` with release
+///   time `r`, deadline `d`, processing volume `p` and an optional penalty for rejecting the job
+///
+/// The maximum deadline and the total processing volume are derived from the jobs
+/// and not part of the document.
+///
+/// A schedule document has the fields
+/// - `version`: the schema version, currently 1
+/// - `m`: the number of processors the schedule is defined for
+/// - `slots`: for each time slot, the ids of the jobs scheduled in it
+///
+/// Documents of another version are rejected.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// serialized form of an Instance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct InstanceDocument {
+    version: u32,
+    m: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    q: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    processors: Option<Vec<Processor>>,
+    jobs: Vec<JobDocument>,
+}
+
+/// serialized form of a job of an Instance together with its penalty
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct JobDocument {
+    #[serde(flatten)]
+    job: Job,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    penalty: Option<usize>,
+}
+
+/// serialized form of a ParallelSchedule
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct ScheduleDocument {
+    version: u32,
+    m: usize,
+    slots: Vec<Vec<usize>>,
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version != SCHEMA_VERSION {
+        return Err(format!(
+            "unsupported schema version {version}, expected {SCHEMA_VERSION}"
+        ));
+    }
+    Ok(())
+}
+
+impl From<Instance> for InstanceDocument {
+    fn from(instance: Instance) -> InstanceDocument {
+        // m and q are derived from the processors, so the document is consistent
+        // even if the fields of the instance are not
+        InstanceDocument {
+            version: SCHEMA_VERSION,
+            m: instance.processors.len(),
+            q: Some(min_wake_up_cost(&instance.processors)),
+            processors: Some(instance.processors),
+            jobs: instance
+                .jobs
+                .into_iter()
+                .map(|job| JobDocument {
+                    penalty: instance.penalties.get(&job.id).copied(),
+                    job,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<InstanceDocument> for Instance {
+    type Error = String;
+    fn try_from(document: InstanceDocument) -> Result<Instance, String> {
+        check_version(document.version)?;
+        let mut penalties: BTreeMap<usize, usize> = BTreeMap::new();
+        for JobDocument { job, penalty } in document.jobs.iter() {
+            if let Some(penalty) = penalty {
+                if penalties.insert(job.id, *penalty).is_some() {
+                    return Err(format!("penalty given twice for job id {}", job.id));
+                }
+            }
+        }
+        let jobs: Vec<Job> = document.jobs.into_iter().map(|job| job.job).collect();
+        let instance = match (document.processors, document.q) {
+            (Some(processors), q) => {
+                if processors.len() != document.m {
+                    return Err(format!(
+                        "{} processors given for m = {}",
+                        processors.len(),
+                        document.m
+                    ));
+                }
+                let instance = Instance::with_processors(jobs, processors);
+                if q.is_some_and(|q| q != instance.q) {
+                    return Err(format!(
+                        "q must be the smallest wake-up cost {} of the processors",
+                        instance.q
+                    ));
+                }
+                instance
+            }
+            (None, Some(q)) => Instance::new(jobs, document.m, q),
+            (None, None) => return Err("either q or processors must be given".to_string()),
+        };
+        Ok(instance.with_penalties(penalties))
+    }
+}
+
+impl From<ParallelSchedule> for ScheduleDocument {
+    fn from(schedule: ParallelSchedule) -> ScheduleDocument {
+        ScheduleDocument {
+            version: SCHEMA_VERSION,
+            m: schedule.m(),
+            slots: schedule.slots().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<ScheduleDocument> for ParallelSchedule {
+    type Error = String;
+    fn try_from(document: ScheduleDocument) -> Result<ParallelSchedule, String> {
+        check_version(document.version)?;
+        if let Some(t) = document
+            .slots
+            .iter()
+            .position(|jobs| jobs.len() > document.m)
+        {
+            return Err(format!(
+                "more than m = {} jobs scheduled in time slot {t}",
+                document.m
+            ));
+        }
+        Ok(ParallelSchedule::from_vec(document.slots, document.m))
+    }
+}
+
+/// Reads a schedule document without checking that its slots hold at most m jobs,
+/// so that a validator can report overfull slots as an invalid schedule.
+/// Fails if the document is malformed or of another version.
+pub fn schedule_from_str_unchecked(json: &str) -> Result<ParallelSchedule, String> {
+    let document: ScheduleDocument = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    check_version(document.version)?;
+    Ok(ParallelSchedule::from_vec(document.slots, document.m))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::pltr;
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{
+            "version": 1,
+            "m": 2,
+            "q": 3,
+            "jobs": [
+                {"id": 0, "r": 0, "d": 4, "p": 2},
+                {"id": 1, "r": 1, "d": 3, "p": 2, "penalty": 7}
+            ]
+        }"#;
+        let instance: Instance = serde_json::from_str(json).unwrap();
+        assert_eq!(instance.d_max, 4);
+        assert_eq!(instance.p_total, 4);
+        assert_eq!(instance.processors, vec![Processor::homogeneous(3); 2]);
+        assert_eq!(instance.penalties, BTreeMap::from([(1, 7)]));
+
+        let serialized = serde_json::to_string(&instance).unwrap();
+        let deserialized: Instance = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(format!("{deserialized:?}"), format!("{instance:?}"));
+
+        let schedule = pltr(&instance);
+        let serialized = serde_json::to_string(&schedule).unwrap();
+        assert!(serialized.starts_with(r#"{"version":1,"m":2,"slots":"#));
+        let deserialized: ParallelSchedule = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, schedule);
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        let other_version = r#"{"version": 2, "m": 1, "q": 1, "jobs": []}"#;
+        assert!(serde_json::from_str::<Instance>(other_version).is_err());
+        let no_power_model = r#"{"version": 1, "m": 1, "jobs": []}"#;
+        assert!(serde_json::from_str::<Instance>(no_power_model).is_err());
+        let too_few_processors = r#"{"version": 1, "m": 2, "jobs": [],
+            "processors": [{"wake_up_cost": 1, "active_power": 1}]}"#;
+        assert!(serde_json::from_str::<Instance>(too_few_processors).is_err());
+        let overfull_slot = r#"{"version": 1, "m": 1, "slots": [[0, 1]]}"#;
+        assert!(serde_json::from_str::<ParallelSchedule>(overfull_slot).is_err());
+        let unchecked = schedule_from_str_unchecked(overfull_slot).unwrap();
+        assert_eq!(unchecked.slots(), [vec![0, 1]]);
+        let other_version = r#"{"version": 2, "m": 1, "slots": []}"#;
+        assert!(schedule_from_str_unchecked(other_version).is_err());
+    }
+}
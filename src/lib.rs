@@ -1,3 +1,21 @@
 pub mod algorithm;
+pub mod compression;
 pub mod types;
 pub mod dataset_parsing;
+pub mod energy;
+pub mod flow;
+pub mod exact;
+pub mod lower_bound;
+pub mod ltr;
+pub mod assignment;
+pub mod non_preemptive;
+pub mod power_states;
+pub mod unrelated;
+pub mod rejection;
+pub mod online;
+pub mod baselines;
+pub mod improve;
+pub mod visualize;
+pub mod gantt;
+pub mod json;
+pub mod instance_generation;
@@ -0,0 +1,123 @@
+use crate::types::Instance;
+use minilp::{ComparisonOp, LinearExpr, OptimizationDirection, Problem, Variable};
+
+/// lower bound on the optimal energy of an instance
+#[derive(Clone, Debug, PartialEq)]
+pub struct LowerBound {
+    /// optimal value of the relaxation
+    pub value: f64,
+    /// fractional number of active processors in each time slot
+    pub activity: Vec<f64>,
+}
+
+/// Computes a lower bound on the optimal energy from the LP relaxation of the
+/// time-indexed formulation:
+/// x_jt is the fraction of job j processed in slot t, y_t the number of active
+/// processors in slot t and w_t the number of processors woken up in slot t.
+/// With a the smallest active power and q the smallest wake-up cost among the processors,
+/// minimizes a * sum_t y_t + q * sum_t w_t subject to
+/// sum_t x_jt = p_j, sum_j x_jt <= y_t, x_jt <= y_t, w_t >= y_t - y_(t-1).
+/// Returns None if the relaxation is infeasible, in which case so is the instance.
+pub fn lp_lower_bound(instance: &Instance) -> Option<LowerBound> {
+    let active_power = instance
+        .processors
+        .iter()
+        .map(|p| p.active_power)
+        .min()
+        .unwrap_or(0);
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+    let y: Vec<Variable> = (0..instance.d_max)
+        .map(|_| problem.add_var(active_power as f64, (0.0, instance.m as f64)))
+        .collect();
+    let w: Vec<Variable> = (0..instance.d_max)
+        .map(|_| problem.add_var(instance.q as f64, (0.0, f64::INFINITY)))
+        .collect();
+    let mut load: Vec<LinearExpr> = (0..instance.d_max)
+        .map(|t| {
+            let mut expr = LinearExpr::empty();
+            expr.add(y[t], -1.0);
+            expr
+        })
+        .collect();
+    for job in instance.jobs.iter() {
+        let mut volume = LinearExpr::empty();
+        for t in job.r..job.d {
+            let x_jt = problem.add_var(0.0, (0.0, 1.0));
+            volume.add(x_jt, 1.0);
+            load[t].add(x_jt, 1.0);
+            problem.add_constraint([(x_jt, 1.0), (y[t], -1.0)], ComparisonOp::Le, 0.0);
+        }
+        problem.add_constraint(volume, ComparisonOp::Eq, job.p as f64);
+    }
+    for (t, expr) in load.into_iter().enumerate() {
+        problem.add_constraint(expr, ComparisonOp::Le, 0.0);
+        if t == 0 {
+            problem.add_constraint([(w[t], 1.0), (y[t], -1.0)], ComparisonOp::Ge, 0.0);
+        } else {
+            problem.add_constraint(
+                [(w[t], 1.0), (y[t], -1.0), (y[t - 1], 1.0)],
+                ComparisonOp::Ge,
+                0.0,
+            );
+        }
+    }
+    let solution = problem.solve().ok()?;
+    Some(LowerBound {
+        value: solution.objective(),
+        activity: y.iter().map(|y_t| solution[*y_t]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::energy::energy;
+    use crate::exact::optimal;
+    use crate::types::Job;
+
+    #[test]
+    fn bound_below_optimum() {
+        let mut jobs: Vec<Job> = (0..5)
+            .map(|i| Job {
+                id: i,
+                r: 2 * i,
+                d: 2 * i + 1,
+                p: 1,
+            })
+            .collect();
+        jobs.push(Job {
+            id: 5,
+            r: 0,
+            d: 9,
+            p: 4,
+        });
+        let instance = Instance::new(jobs, 2, 3);
+        let bound = lp_lower_bound(&instance).unwrap();
+        let optimum = energy(&optimal(&instance).unwrap(), &instance)
+            .unwrap()
+            .total;
+        assert!(bound.value >= instance.p_total as f64 - 1e-6);
+        assert!(bound.value <= optimum as f64 + 1e-6);
+        assert_eq!(bound.activity.len(), instance.d_max);
+    }
+
+    #[test]
+    fn infeasible_relaxation() {
+        let jobs = vec![
+            Job {
+                id: 0,
+                r: 0,
+                d: 2,
+                p: 2,
+            },
+            Job {
+                id: 1,
+                r: 0,
+                d: 2,
+                p: 2,
+            },
+        ];
+        let instance = Instance::new(jobs, 1, 1);
+        assert_eq!(lp_lower_bound(&instance), None);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::algorithm::{binary_search_maximum, validate, PltrError};
+use crate::types::{Instance, Schedule};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// state of the single processor in a time slot as decided by Left-to-Right
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slot {
+    Undecided,
+    Idle,
+    Busy,
+}
+
+/// classic Left-to-Right algorithm for a single processor:
+/// alternately keeps the processor idle and busy for as long as possible
+/// panics if the instance is infeasible or malformed
+pub fn ltr(instance: &Instance) -> Schedule {
+    try_ltr(instance).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// classic Left-to-Right algorithm for a single processor,
+/// returning an error instead of panicking
+pub fn try_ltr(instance: &Instance) -> Result<Schedule, PltrError> {
+    validate(instance)?;
+    let mut slots = vec![Slot::Undecided; instance.d_max];
+    if !is_feasible(&slots, instance) {
+        return Err(PltrError::InfeasibleInstance);
+    }
+    let mut t = 0;
+    while t < instance.d_max {
+        t = keep(Slot::Idle, t, &mut slots, instance)?;
+        if t < instance.d_max {
+            t = keep(Slot::Busy, t, &mut slots, instance)?;
+        }
+    }
+    Ok(edf(&slots, instance))
+}
+
+/// helper for ltr: marks the longest feasible interval from `from` on with `state`
+fn keep(
+    state: Slot,
+    from: usize,
+    slots: &mut [Slot],
+    instance: &Instance,
+) -> Result<usize, PltrError> {
+    let can_keep = |upto| {
+        let mut my_slots = slots.to_vec();
+        my_slots[from..upto].fill(state);
+        is_feasible(&my_slots, instance)
+    };
+    let upto = binary_search_maximum(can_keep, from, instance.d_max + 1).ok_or_else(|| {
+        PltrError::InvariantViolation(format!("bounded instance became infeasible at slot {from}"))
+    })?;
+    slots[from..upto].fill(state);
+    Ok(upto)
+}
+
+/// Whether some schedule processes all jobs outside of idle slots and keeps every busy slot busy.
+/// By the Mendelsohn-Dulmage theorem, this is the case iff all jobs can be scheduled outside
+/// of idle slots and, independently, every busy slot can be assigned a distinct unit of work.
+/// Both are decided greedily in order of earliest deadline.
+fn is_feasible(slots: &[Slot], instance: &Instance) -> bool {
+    let mut jobs: Vec<usize> = (0..instance.jobs.len()).collect();
+    jobs.sort_by_key(|&j| instance.jobs[j].r);
+    // (deadline, remaining processing volume) of released jobs
+    let mut pending: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new();
+    let mut coverable: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new();
+    let mut next = 0;
+    for (t, slot) in slots.iter().enumerate() {
+        while next < jobs.len() && instance.jobs[jobs[next]].r <= t {
+            let job = &instance.jobs[jobs[next]];
+            pending.push(Reverse((job.d, job.p)));
+            coverable.push(Reverse((job.d, job.p)));
+            next += 1;
+        }
+        if let Some(Reverse((d, _))) = pending.peek() {
+            if *d <= t {
+                return false;
+            }
+        }
+        while let Some(Reverse((d, _))) = coverable.peek() {
+            if *d > t {
+                break;
+            }
+            coverable.pop();
+        }
+        if *slot != Slot::Idle {
+            if let Some(Reverse((d, p))) = pending.pop() {
+                if p > 1 {
+                    pending.push(Reverse((d, p - 1)));
+                }
+            }
+        }
+        if *slot == Slot::Busy {
+            match coverable.pop() {
+                None => return false,
+                Some(Reverse((d, p))) => {
+                    if p > 1 {
+                        coverable.push(Reverse((d, p - 1)));
+                    }
+                }
+            }
+        }
+    }
+    pending.is_empty()
+}
+
+/// schedules the jobs by earliest deadline first on the busy slots
+fn edf(slots: &[Slot], instance: &Instance) -> Schedule {
+    let mut jobs: Vec<usize> = (0..instance.jobs.len()).collect();
+    jobs.sort_by_key(|&j| instance.jobs[j].r);
+    // (deadline, job index) of released jobs with remaining processing volume
+    let mut pending: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new();
+    let mut remaining: Vec<usize> = instance.jobs.iter().map(|job| job.p).collect();
+    let mut schedule = Schedule(vec![None; slots.len()]);
+    let mut next = 0;
+    for (t, slot) in slots.iter().enumerate() {
+        while next < jobs.len() && instance.jobs[jobs[next]].r <= t {
+            pending.push(Reverse((instance.jobs[jobs[next]].d, jobs[next])));
+            next += 1;
+        }
+        if *slot == Slot::Busy {
+            if let Some(Reverse((d, j))) = pending.pop() {
+                schedule.0[t] = Some(instance.jobs[j].id);
+                remaining[j] -= 1;
+                if remaining[j] > 0 {
+                    pending.push(Reverse((d, j)));
+                }
+            }
+        }
+    }
+    schedule
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::pltr;
+    use crate::energy::energy;
+    use crate::types::{jobs_from, ParallelSchedule, EXAMPLE_JOBS};
+
+    #[test]
+    fn same_energy_as_pltr_on_one_processor() {
+        let jobs = jobs_from(&EXAMPLE_JOBS[..5]);
+        let instance = Instance::new(jobs, 1, 3);
+        let schedule = ParallelSchedule::from(ltr(&instance));
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert_eq!(
+            energy(&schedule, &instance).unwrap(),
+            energy(&pltr(&instance), &instance).unwrap()
+        );
+    }
+
+    #[test]
+    fn single_processor_conversion() {
+        let schedule = Schedule(vec![Some(1), None, Some(2)]);
+        let parallel = ParallelSchedule::from(schedule);
+        assert_eq!(
+            parallel,
+            ParallelSchedule::from_vec(vec![vec![1], vec![], vec![2]], 1)
+        );
+        let single = Schedule::try_from(&parallel).unwrap();
+        assert_eq!(single.0, vec![Some(1), None, Some(2)]);
+        let two_jobs = ParallelSchedule::from_vec(vec![vec![1, 2]], 2);
+        assert!(Schedule::try_from(&two_jobs).is_err());
+    }
+}
@@ -1,17 +1,508 @@
-use std::io;
-use std::time::Instant;
+use clap::{Parser, Subcommand, ValueEnum};
 use pltr::algorithm;
 use pltr::dataset_parsing;
+use pltr::energy::energy;
+use pltr::flow::{Dinic, EdmondsKarp, PushRelabel};
+use pltr::gantt;
+use pltr::improve::improve;
+use pltr::instance_generation::{self, GeneratedInstance, GeneratorConfig};
+use pltr::json;
+use pltr::types::{Instance, ParallelSchedule};
+use pltr::unrelated;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Instant;
+use tracing_subscriber::EnvFilter;
 
-/// Starts a macro benchmark of pltr using the scheduling dataset from
-/// `Exact and heuristic algorithms for scheduling jobs
+/// scheduling dataset from `Exact and heuristic algorithms for scheduling jobs
 /// with time windows on unrelated parallel machines`
 /// (Giorgi Tadumadze, Simon Emde, Heiko Diefenbach)
-fn main() -> io::Result<()> {
-    let start = Instant::now();
-    let instances = dataset_parsing::parse_csv_to_instance("datasets/exact_and_heuristic_scheduling/instances.csv").unwrap();
-    let _ = instances.iter().map(algorithm::pltr).collect::<Vec<_>>();
-    let duration = start.elapsed();
-    println!("Time taken: {:?}", duration);
+const DATASET: &str = "datasets/exact_and_heuristic_scheduling/instances.csv";
+
+/// Minimum-energy scheduling with Parallel Left-to-Right.
+/// Progress of pltr is logged according to the RUST_LOG environment variable, e.g. RUST_LOG=pltr=debug
+/// and the flow network of every probe is logged as DOT with RUST_LOG=pltr::network=trace
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Schedules an instance and writes the schedule
+    Solve {
+        /// instance file, - for standard input
+        input: PathBuf,
+        #[arg(long, value_enum, default_value_t = InstanceFormat::Json)]
+        input_format: InstanceFormat,
+        /// index of the instance if the file contains several
+        #[arg(long, default_value_t = 0)]
+        index: usize,
+        #[arg(long, value_enum, default_value_t = Backend::EdmondsKarp)]
+        backend: Backend,
+        /// post-optimise the schedule of pltr by local search
+        #[arg(long)]
+        improve: bool,
+        /// output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = ScheduleFormat::Json)]
+        output_format: ScheduleFormat,
+    },
+    /// Generates synthetic instances
+    Generate {
+        #[arg(long, value_enum, default_value_t = Distribution::Random)]
+        distribution: Distribution,
+        /// number of jobs
+        #[arg(short, long, default_value_t = 20)]
+        n: usize,
+        /// number of processors
+        #[arg(short, long, default_value_t = 2)]
+        m: usize,
+        /// wake-up cost
+        #[arg(short, long, default_value_t = 3)]
+        q: usize,
+        /// time horizon the jobs are released and due in
+        #[arg(long, default_value_t = 100)]
+        horizon: usize,
+        /// average length of the job windows
+        #[arg(long, default_value_t = 10)]
+        window: usize,
+        /// number of valleys of the valley distribution
+        #[arg(long, default_value_t = 4)]
+        valleys: usize,
+        /// number of instances, written as a list if more than one
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// seed of the first instance, the following ones use the next seeds,
+        /// drawn at random if omitted
+        #[arg(long)]
+        seed: Option<u64>,
+        /// output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = TextOrJson::Json)]
+        output_format: TextOrJson,
+    },
+    /// Checks a schedule against an instance and reports its energy,
+    /// exits with status 1 if the schedule is invalid and 2 if a file cannot be read or parsed
+    Validate {
+        /// instance file, - for standard input
+        instance: PathBuf,
+        /// schedule file in JSON
+        schedule: PathBuf,
+        #[arg(long, value_enum, default_value_t = InstanceFormat::Json)]
+        input_format: InstanceFormat,
+        /// index of the instance if the file contains several
+        #[arg(long, default_value_t = 0)]
+        index: usize,
+        #[arg(long, value_enum, default_value_t = TextOrJson::Text)]
+        output_format: TextOrJson,
+    },
+    /// Runs pltr on every instance of a dataset and reports per-instance metrics
+    Bench {
+        /// instance file, - for standard input
+        #[arg(default_value = DATASET)]
+        input: PathBuf,
+        #[arg(long, value_enum, default_value_t = InstanceFormat::Csv)]
+        input_format: InstanceFormat,
+        /// maximum flow backends to run, all if omitted
+        #[arg(long, value_enum)]
+        backend: Vec<Backend>,
+        /// also run the heuristic for unrelated machines, only for CSV datasets
+        #[arg(long)]
+        unrelated: bool,
+        /// output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = MetricsFormat::Text)]
+        output_format: MetricsFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InstanceFormat {
+    /// an instance or a list of instances in the JSON schema of pltr::json
+    Json,
+    /// the CSV format of the dataset
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScheduleFormat {
+    Json,
+    Text,
+    Svg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TextOrJson {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MetricsFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Distribution {
+    /// windows anywhere in the horizon
+    Random,
+    /// the horizon is split into `valleys` parts and the jobs are dealt round-robin to them
+    Valley,
+}
+
+impl From<Distribution> for instance_generation::Distribution {
+    fn from(distribution: Distribution) -> Self {
+        match distribution {
+            Distribution::Random => instance_generation::Distribution::Random,
+            Distribution::Valley => instance_generation::Distribution::Valley,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Backend {
+    EdmondsKarp,
+    Dinic,
+    PushRelabel,
+}
+
+impl Backend {
+    fn name(self) -> &'static str {
+        match self {
+            Backend::EdmondsKarp => "edmonds-karp",
+            Backend::Dinic => "dinic",
+            Backend::PushRelabel => "push-relabel",
+        }
+    }
+    fn solve(self, instance: &Instance) -> Result<ParallelSchedule, algorithm::PltrError> {
+        match self {
+            Backend::EdmondsKarp => algorithm::try_pltr_with::<EdmondsKarp>(instance),
+            Backend::Dinic => algorithm::try_pltr_with::<Dinic>(instance),
+            Backend::PushRelabel => algorithm::try_pltr_with::<PushRelabel>(instance),
+        }
+    }
+}
+
+/// metrics of one solver run on one instance
+#[derive(Serialize)]
+struct Metrics {
+    instance: usize,
+    solver: &'static str,
+    n: usize,
+    m: usize,
+    d_max: usize,
+    micros: u128,
+    solved: bool,
+    /// why the solver failed, if it did
+    error: Option<String>,
+    energy: Option<usize>,
+    busy_time: Option<usize>,
+    bridged_idle_time: Option<usize>,
+    wakeups: Option<usize>,
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(io::stderr)
+        .init();
+    if let Err(e) = run(Cli::parse().command) {
+        eprintln!("error: {e}");
+        process::exit(2);
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Solve {
+            input,
+            input_format,
+            index,
+            backend,
+            improve: improve_schedule,
+            output,
+            output_format,
+        } => {
+            let instance = read_instance(&input, input_format, index)?;
+            let mut schedule = backend.solve(&instance)?;
+            if improve_schedule {
+                schedule = improve(&schedule, &instance)?;
+            }
+            eprintln!("energy: {}", energy(&schedule, &instance)?.total);
+            let content = match output_format {
+                ScheduleFormat::Json => serde_json::to_string(&schedule)?,
+                ScheduleFormat::Text => schedule.to_string(),
+                ScheduleFormat::Svg => {
+                    gantt::render_svg(&schedule, &instance, gantt::DEFAULT_SLOT_WIDTH)
+                }
+            };
+            write_output(output.as_deref(), &content)
+        }
+        Command::Generate {
+            distribution,
+            n,
+            m,
+            q,
+            horizon,
+            window,
+            valleys,
+            count,
+            seed,
+            output,
+            output_format,
+        } => {
+            let config = GeneratorConfig {
+                distribution: distribution.into(),
+                n,
+                m,
+                q,
+                horizon,
+                window,
+                valleys,
+            };
+            let seed = seed.unwrap_or_else(rand::random);
+            let instances = (0..count as u64)
+                .map(|i| instance_generation::generate(&config, seed.wrapping_add(i)))
+                .collect::<Result<Vec<GeneratedInstance>, String>>()?;
+            let content = match (output_format, instances.as_slice()) {
+                (TextOrJson::Json, [instance]) => serde_json::to_string_pretty(instance)?,
+                (TextOrJson::Json, instances) => serde_json::to_string_pretty(instances)?,
+                (TextOrJson::Text, instances) => instances
+                    .iter()
+                    .map(|generated| format!("seed: {}\n{}", generated.seed, generated.instance))
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            };
+            write_output(output.as_deref(), &content)
+        }
+        Command::Validate {
+            instance,
+            schedule,
+            input_format,
+            index,
+            output_format,
+        } => {
+            let instance = read_instance(&instance, input_format, index)?;
+            // overfull slots are reported as an invalid schedule rather than a malformed document
+            let schedule = json::schedule_from_str_unchecked(&read_input(&schedule)?)?;
+            let validity = schedule
+                .is_valid_for(&instance)
+                .and_then(|()| energy(&schedule, &instance));
+            let report = match output_format {
+                TextOrJson::Text => match &validity {
+                    Ok(cost) => format!(
+                        "valid, energy {} (busy {}, bridged idle {}, {} wake-ups)",
+                        cost.total, cost.busy_time, cost.bridged_idle_time, cost.wakeups
+                    ),
+                    Err(e) => format!("invalid: {e}"),
+                },
+                TextOrJson::Json => {
+                    let cost = validity.as_ref().ok();
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "valid": validity.is_ok(),
+                        "error": validity.as_ref().err(),
+                        "energy": cost.map(|cost| cost.total),
+                        "busy_time": cost.map(|cost| cost.busy_time),
+                        "bridged_idle_time": cost.map(|cost| cost.bridged_idle_time),
+                        "wakeups": cost.map(|cost| cost.wakeups),
+                    }))?
+                }
+            };
+            println!("{report}");
+            if validity.is_err() {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Bench {
+            input,
+            input_format,
+            mut backend,
+            unrelated,
+            output,
+            output_format,
+        } => {
+            if backend.is_empty() {
+                backend = vec![Backend::EdmondsKarp, Backend::Dinic, Backend::PushRelabel];
+            }
+            // read once, as standard input cannot be read again for the unrelated machines
+            let content = read_input(&input)?;
+            let instances = parse_instances(&content, input_format)?;
+            let mut metrics: Vec<Metrics> = Vec::new();
+            for &solver in backend.iter() {
+                for (i, instance) in instances.iter().enumerate() {
+                    let start = Instant::now();
+                    let result = solver.solve(instance);
+                    let micros = start.elapsed().as_micros();
+                    let result = result
+                        .map_err(|e| e.to_string())
+                        .and_then(|schedule| energy(&schedule, instance));
+                    let error = result.as_ref().err().cloned();
+                    let cost = result.ok();
+                    metrics.push(Metrics {
+                        instance: i,
+                        solver: solver.name(),
+                        n: instance.jobs.len(),
+                        m: instance.m,
+                        d_max: instance.d_max,
+                        micros,
+                        solved: cost.is_some(),
+                        error,
+                        energy: cost.map(|cost| cost.total),
+                        busy_time: cost.map(|cost| cost.busy_time),
+                        bridged_idle_time: cost.map(|cost| cost.bridged_idle_time),
+                        wakeups: cost.map(|cost| cost.wakeups),
+                    });
+                }
+            }
+            if unrelated {
+                let InstanceFormat::Csv = input_format else {
+                    return Err("unrelated machines are only read from CSV datasets".into());
+                };
+                let instances =
+                    dataset_parsing::parse_csv_reader_to_unrelated_instances(content.as_bytes())?;
+                for (i, instance) in instances.iter().enumerate() {
+                    let start = Instant::now();
+                    let schedule = unrelated::unrelated(instance);
+                    let micros = start.elapsed().as_micros();
+                    let cost =
+                        schedule.map(|schedule| unrelated::unrelated_energy(&schedule, instance));
+                    metrics.push(Metrics {
+                        instance: i,
+                        solver: "unrelated",
+                        n: instance.jobs.len(),
+                        m: instance.m,
+                        d_max: instance.d_max,
+                        micros,
+                        solved: cost.is_some(),
+                        error: cost
+                            .is_none()
+                            .then(|| "no feasible schedule found".to_string()),
+                        energy: cost.map(|cost| cost.total),
+                        busy_time: cost.map(|cost| cost.busy_time),
+                        bridged_idle_time: cost.map(|cost| cost.bridged_idle_time),
+                        wakeups: cost.map(|cost| cost.wakeups),
+                    });
+                }
+            }
+            let content = match output_format {
+                MetricsFormat::Text => metrics_table(&metrics),
+                MetricsFormat::Csv => {
+                    let mut writer = csv::Writer::from_writer(Vec::new());
+                    for row in metrics.iter() {
+                        writer.serialize(row)?;
+                    }
+                    String::from_utf8(writer.into_inner()?)?
+                }
+                MetricsFormat::Json => serde_json::to_string_pretty(&metrics)?,
+            };
+            write_output(output.as_deref(), &content)
+        }
+    }
+}
+
+/// one line per run followed by the number of solved instances and total time of each solver
+fn metrics_table(metrics: &[Metrics]) -> String {
+    let optional = |value: Option<usize>| value.map_or("-".to_string(), |v| v.to_string());
+    let mut lines = vec![format!(
+        "{:>8} {:>14} {:>5} {:>3} {:>6} {:>10} {:>8} {:>8} {:>8}  error",
+        "instance", "solver", "n", "m", "d_max", "micros", "energy", "busy", "wake-ups"
+    )];
+    for row in metrics {
+        lines.push(format!(
+            "{:>8} {:>14} {:>5} {:>3} {:>6} {:>10} {:>8} {:>8} {:>8}  {}",
+            row.instance,
+            row.solver,
+            row.n,
+            row.m,
+            row.d_max,
+            row.micros,
+            optional(row.energy),
+            optional(row.busy_time),
+            optional(row.wakeups),
+            row.error.as_deref().unwrap_or("-")
+        ));
+    }
+    let mut solvers: Vec<&str> = metrics.iter().map(|row| row.solver).collect();
+    solvers.dedup();
+    for solver in solvers {
+        let rows = metrics.iter().filter(|row| row.solver == solver);
+        let solved = rows.clone().filter(|row| row.solved).count();
+        let failed = rows.clone().filter(|row| row.error.is_some()).count();
+        let micros: u128 = rows.clone().map(|row| row.micros).sum();
+        lines.push(format!(
+            "{solver}: {solved} of {} instances solved, {failed} failed, time taken: {:?}",
+            rows.count(),
+            std::time::Duration::from_micros(micros as u64)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    fs::read_to_string(path)
+}
+
+/// reads all instances of the file, where a JSON file holds an instance or a list of instances
+fn read_instances(path: &Path, format: InstanceFormat) -> Result<Vec<Instance>, Box<dyn Error>> {
+    parse_instances(&read_input(path)?, format)
+}
+
+fn parse_instances(content: &str, format: InstanceFormat) -> Result<Vec<Instance>, Box<dyn Error>> {
+    match format {
+        InstanceFormat::Json => {
+            let value: serde_json::Value = serde_json::from_str(content)?;
+            if value.is_array() {
+                Ok(serde_json::from_value(value)?)
+            } else {
+                Ok(vec![serde_json::from_value(value)?])
+            }
+        }
+        InstanceFormat::Csv => dataset_parsing::parse_csv_reader_to_instance(content.as_bytes()),
+    }
+}
+
+fn read_instance(
+    path: &Path,
+    format: InstanceFormat,
+    index: usize,
+) -> Result<Instance, Box<dyn Error>> {
+    let mut instances = read_instances(path, format)?;
+    if index >= instances.len() {
+        return Err(format!(
+            "instance {index} requested, but the file contains {}",
+            instances.len()
+        )
+        .into());
+    }
+    Ok(instances.swap_remove(index))
+}
+
+/// writes to the file or to standard output, where a closed standard output is not an error
+fn write_output(path: Option<&Path>, content: &str) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => fs::write(path, content)?,
+        None => match writeln!(io::stdout(), "{content}") {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+            result => result?,
+        },
+    }
     Ok(())
 }
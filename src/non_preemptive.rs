@@ -0,0 +1,135 @@
+use crate::algorithm::validate;
+use crate::types::{Instance, Job, ParallelSchedule, Processor};
+
+/// Greedy heuristic for non-preemptive scheduling: every job runs in p consecutive
+/// time slots on a single processor. Jobs are placed in order of increasing slack d - r - p,
+/// each on the processor and at the start time that increase the energy of that processor
+/// the least, breaking ties by the earliest start.
+/// A processor pays for its own busy slots, bridging idle gaps shorter than its
+/// bridging threshold, so the score is what the placement costs with jobs kept on their
+/// processors; energy may report less, as it lets the processor levels move between processors.
+/// Returns None if the instance is malformed or some job cannot be placed, which does not imply that the instance
+/// is infeasible.
+pub fn non_preemptive(instance: &Instance) -> Option<ParallelSchedule> {
+    validate(instance).ok()?;
+    let mut jobs: Vec<usize> = (0..instance.jobs.len()).collect();
+    jobs.sort_by_key(|&j| {
+        let job = &instance.jobs[j];
+        (job.d - job.r - job.p, job.d)
+    });
+    let mut busy: Vec<Vec<bool>> = vec![vec![false; instance.d_max]; instance.m];
+    let mut slots: Vec<Vec<usize>> = vec![Vec::new(); instance.d_max];
+    for j in jobs {
+        let job = &instance.jobs[j];
+        // (energy increase, start, processor)
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (i, timeline) in busy.iter().enumerate() {
+            for (start, increase) in placements(timeline, job, &instance.processors[i]) {
+                if best.is_none_or(|(cost, earliest, _)| (increase, start) < (cost, earliest)) {
+                    best = Some((increase, start, i));
+                }
+            }
+        }
+        let (_, start, i) = best?;
+        for t in start..start + job.p {
+            busy[i][t] = true;
+            slots[t].push(job.id);
+        }
+    }
+    Some(ParallelSchedule::from_vec(slots, instance.m))
+}
+
+/// start times at which the job fits into the idle slots of a processor's timeline,
+/// each with the resulting increase of the processor's energy
+fn placements(timeline: &[bool], job: &Job, processor: &Processor) -> Vec<(usize, usize)> {
+    let len = timeline.len();
+    // end of the last busy slot before t and start of the first busy slot from t on
+    let mut last_end: Vec<Option<usize>> = vec![None; len + 1];
+    for t in 0..len {
+        last_end[t + 1] = if timeline[t] {
+            Some(t + 1)
+        } else {
+            last_end[t]
+        };
+    }
+    let mut next_start: Vec<Option<usize>> = vec![None; len + 1];
+    for t in (0..len).rev() {
+        next_start[t] = if timeline[t] {
+            Some(t)
+        } else {
+            next_start[t + 1]
+        };
+    }
+    let threshold = processor.bridging_threshold();
+    let (q, a) = (processor.wake_up_cost, processor.active_power);
+    // cost of an idle gap between two busy intervals: bridged or a wake-up afterwards
+    let gap = |length: usize| if length < threshold { length * a } else { q };
+    let mut result = Vec::new();
+    for start in job.r..job.d - job.p + 1 {
+        let end = start + job.p;
+        if next_start[start].is_some_and(|t| t < end) {
+            continue;
+        }
+        let (before, after) = (last_end[start], next_start[end]);
+        let old = match (before, after) {
+            (Some(b), Some(n)) => gap(n - b),
+            (None, Some(_)) => q,
+            _ => 0,
+        };
+        let new = before.map_or(q, |b| gap(start - b)) + after.map_or(0, |n| gap(n - end));
+        // splitting a gap never makes it cheaper than the busy slots that split it
+        result.push((start, a * job.p + new - old));
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::energy::energy;
+
+    #[test]
+    fn contiguous_and_packed() {
+        let jobs: Vec<Job> = [(0, 10, 3), (2, 8, 2), (0, 4, 1), (5, 12, 4)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(r, d, p))| Job { id, r, d, p })
+            .collect();
+        let instance = Instance::new(jobs, 2, 4);
+        let schedule = non_preemptive(&instance).unwrap();
+        assert!(schedule.is_valid_non_preemptive_for(&instance).is_ok());
+        assert_eq!(energy(&schedule, &instance).unwrap().wakeups, 2);
+    }
+
+    #[test]
+    fn preempted_schedule_is_rejected() {
+        let jobs = vec![Job {
+            id: 0,
+            r: 0,
+            d: 4,
+            p: 2,
+        }];
+        let instance = Instance::new(jobs, 1, 1);
+        let schedule = ParallelSchedule::from_vec(vec![vec![0], vec![], vec![0], vec![]], 1);
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert!(schedule.is_valid_non_preemptive_for(&instance).is_err());
+    }
+
+    #[test]
+    fn joins_busy_processor() {
+        let jobs: Vec<Job> = [(0, 2, 2), (0, 6, 2), (4, 6, 1)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(r, d, p))| Job { id, r, d, p })
+            .collect();
+        let instance = Instance::new(jobs, 2, 4);
+        let schedule = non_preemptive(&instance).unwrap();
+        assert_eq!(
+            schedule,
+            ParallelSchedule::from_vec(
+                vec![vec![0], vec![0], vec![1], vec![1], vec![2], vec![]],
+                2
+            )
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::algorithm::{create_graph, try_pltr};
+use crate::energy::energy;
+use crate::flow::{EdmondsKarp, FlowNetwork};
+use crate::types::{Instance, Job, ParallelSchedule, Processor};
+
+/// Online scheduler for m processors.
+/// Jobs are revealed at their release time and the jobs of each time slot are committed
+/// without knowledge of later jobs.
+/// In every slot, the scheduler runs as few jobs as keep the known jobs feasible, but at least
+/// as many as there are processors on, choosing jobs of least laxity.
+/// Processors are numbered such that processor k runs whenever at least k jobs run.
+/// A processor is woken up when more jobs have to run than processors are on, and an idle
+/// processor is turned off once it has been idle for its bridging threshold, which costs at most
+/// twice as much as turning it off right away with knowledge of its next busy slot.
+/// Since later jobs are unknown, jobs may miss their deadlines on instances that are feasible
+/// offline.
+#[derive(Clone, Debug)]
+pub struct OnlineScheduler {
+    /// processor k at index k - 1
+    processors: Vec<Processor>,
+    /// the next time slot to be decided
+    t: usize,
+    /// released jobs with their remaining processing volume
+    pending: Vec<(Job, usize)>,
+    slots: Vec<Vec<usize>>,
+    /// number of idle slots of each processor that is on, processor k at index k - 1
+    idle: Vec<usize>,
+    energy: usize,
+    wakeups: usize,
+}
+
+impl OnlineScheduler {
+    /// scheduler for m identical processors with wake-up cost q and unit active power
+    pub fn new(m: usize, q: usize) -> OnlineScheduler {
+        OnlineScheduler::with_processors(vec![Processor::homogeneous(q); m])
+    }
+
+    /// scheduler for heterogeneous processors, where processor k at index k - 1 runs
+    /// whenever at least k jobs run
+    pub fn with_processors(processors: Vec<Processor>) -> OnlineScheduler {
+        OnlineScheduler {
+            processors,
+            t: 0,
+            pending: Vec::new(),
+            slots: Vec::new(),
+            idle: Vec::new(),
+            energy: 0,
+            wakeups: 0,
+        }
+    }
+
+    /// the next time slot to be decided
+    pub fn time(&self) -> usize {
+        self.t
+    }
+
+    /// reveals a job, which must not be released before the next time slot to be decided
+    pub fn release(&mut self, job: Job) -> Result<(), String> {
+        if job.r < self.t {
+            return Err(format!(
+                "job {} released at {} after time slot {} was decided",
+                job.id, job.r, self.t
+            ));
+        }
+        let p = job.p;
+        self.pending.push((job, p));
+        Ok(())
+    }
+
+    /// decides the next time slot and returns the ids of the jobs run in it,
+    /// fails if a job misses its deadline
+    pub fn step(&mut self) -> Result<&[usize], String> {
+        let t = self.t;
+        let mut available: Vec<usize> = (0..self.pending.len())
+            .filter(|&i| self.pending[i].0.r <= t)
+            .collect();
+        available.sort_by_key(|&i| {
+            let (job, remaining) = &self.pending[i];
+            (job.d.saturating_sub(t + remaining), job.d)
+        });
+        let at_least = self.idle.len().min(available.len());
+        let most = self.processors.len().min(available.len());
+        let busy = (at_least..most + 1)
+            .find(|&busy| self.is_feasible_after(&available[..busy]))
+            .unwrap_or(most);
+        let mut jobs: Vec<usize> = Vec::with_capacity(busy);
+        for &i in available[..busy].iter() {
+            self.pending[i].1 -= 1;
+            jobs.push(self.pending[i].0.id);
+        }
+        self.pending.retain(|(_, remaining)| *remaining > 0);
+        self.update_power(busy);
+        self.slots.push(jobs);
+        self.t += 1;
+        if let Some((job, remaining)) = self.pending.iter().find(|(job, _)| job.d <= self.t) {
+            return Err(format!(
+                "job {} missed its deadline {} with {} units remaining",
+                job.id, job.d, remaining
+            ));
+        }
+        Ok(&self.slots[t])
+    }
+
+    /// wakes up processors up to busy and turns off processors idle for their bridging threshold
+    fn update_power(&mut self, busy: usize) {
+        while self.idle.len() < busy {
+            self.energy += self.processors[self.idle.len()].wake_up_cost;
+            self.idle.push(0);
+            self.wakeups += 1;
+        }
+        for (k, idle) in self.idle.iter_mut().enumerate() {
+            *idle = if k < busy { 0 } else { *idle + 1 };
+            self.energy += self.processors[k].active_power;
+        }
+        while let Some(&idle) = self.idle.last() {
+            if idle < self.processors[self.idle.len() - 1].bridging_threshold() {
+                break;
+            }
+            self.idle.pop();
+        }
+    }
+
+    /// whether the known jobs can still be completed after running the given pending jobs in
+    /// the current time slot
+    fn is_feasible_after(&self, running: &[usize]) -> bool {
+        let from = self.t + 1;
+        let mut jobs: Vec<Job> = Vec::with_capacity(self.pending.len());
+        for (i, (job, remaining)) in self.pending.iter().enumerate() {
+            let remaining = remaining - running.contains(&i) as usize;
+            if remaining == 0 {
+                continue;
+            }
+            if job.d < from + remaining {
+                return false;
+            }
+            jobs.push(Job {
+                r: job.r.saturating_sub(from),
+                d: job.d - from,
+                p: remaining,
+                ..job.clone()
+            });
+        }
+        let remaining_instance = Instance::with_processors(jobs, self.processors.clone());
+        let mut nw: EdmondsKarp = create_graph(&remaining_instance);
+        nw.augment() == remaining_instance.p_total as i32
+    }
+
+    /// energy of the decisions so far: the active power of every processor for each slot it is on
+    /// and its wake-up cost for each wake-up
+    pub fn energy(&self) -> usize {
+        self.energy
+    }
+
+    /// number of times a processor was woken up
+    pub fn wakeups(&self) -> usize {
+        self.wakeups
+    }
+
+    /// the schedule decided so far
+    pub fn schedule(&self) -> ParallelSchedule {
+        ParallelSchedule::from_vec(self.slots.clone(), self.processors.len())
+    }
+}
+
+/// outcome of feeding an instance to the online scheduler
+#[derive(Debug, PartialEq)]
+pub struct Simulation {
+    pub schedule: ParallelSchedule,
+    /// energy of the wake-up and sleep decisions of the online scheduler
+    pub online_energy: usize,
+    /// energy of the online schedule if processors are turned off optimally in hindsight
+    pub hindsight_energy: usize,
+    /// energy of the offline pltr schedule
+    pub pltr_energy: usize,
+}
+
+impl Simulation {
+    /// ratio between the online energy and the energy of pltr
+    pub fn ratio(&self) -> f64 {
+        if self.pltr_energy == 0 {
+            return 1.0;
+        }
+        self.online_energy as f64 / self.pltr_energy as f64
+    }
+}
+
+/// Feeds the jobs of the instance to the online scheduler in order of release time
+/// and compares the result with offline pltr.
+/// The scheduler opens the processors in Instance::processor_order.
+/// Fails if pltr fails on the instance or a job misses its deadline online.
+pub fn simulate(instance: &Instance) -> Result<Simulation, String> {
+    let offline = try_pltr(instance).map_err(|error| error.to_string())?;
+    let mut jobs = instance.jobs.clone();
+    jobs.sort_by_key(|job| job.r);
+    let mut jobs = jobs.into_iter().peekable();
+    let processors = instance
+        .processor_order()
+        .into_iter()
+        .map(|i| instance.processors[i])
+        .collect();
+    let mut scheduler = OnlineScheduler::with_processors(processors);
+    while scheduler.time() < instance.d_max {
+        while let Some(job) = jobs.next_if(|job| job.r <= scheduler.time()) {
+            scheduler.release(job)?;
+        }
+        scheduler.step()?;
+    }
+    let schedule = scheduler.schedule();
+    schedule.is_valid_for(instance)?;
+    Ok(Simulation {
+        online_energy: scheduler.energy(),
+        hindsight_energy: energy(&schedule, instance)?.total,
+        pltr_energy: energy(&offline, instance)?.total,
+        schedule,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::PltrError;
+    use crate::types::{jobs_from, EXAMPLE_JOBS};
+
+    #[test]
+    fn online_within_twice_of_hindsight() {
+        let jobs = jobs_from(&EXAMPLE_JOBS);
+        let instance = Instance::new(jobs, 3, 3);
+        let simulation = simulate(&instance).unwrap();
+        assert!(simulation.schedule.is_valid_for(&instance).is_ok());
+        assert!(simulation.hindsight_energy <= simulation.online_energy);
+        assert!(simulation.online_energy <= 2 * simulation.hindsight_energy);
+        assert!(simulation.ratio() >= 1.0);
+    }
+
+    #[test]
+    fn heterogeneous_processors() {
+        let jobs = jobs_from(&EXAMPLE_JOBS);
+        let processors = vec![
+            Processor {
+                wake_up_cost: 2,
+                active_power: 2,
+            },
+            Processor {
+                wake_up_cost: 6,
+                active_power: 1,
+            },
+            Processor::homogeneous(3),
+        ];
+        let instance = Instance::with_processors(jobs, processors);
+        let simulation = simulate(&instance).unwrap();
+        assert!(simulation.hindsight_energy <= simulation.online_energy);
+        assert!(simulation.online_energy <= 2 * simulation.hindsight_energy);
+    }
+
+    #[test]
+    fn compares_with_optimum() {
+        use crate::exact::optimal;
+        let instance = Instance::new(jobs_from(&EXAMPLE_JOBS[..5]), 1, 3);
+        let simulation = simulate(&instance).unwrap();
+        let optimum = energy(&optimal(&instance).unwrap(), &instance)
+            .unwrap()
+            .total;
+        assert!(optimum <= simulation.pltr_energy);
+        assert!(optimum <= simulation.hindsight_energy);
+        assert!(simulation.online_energy <= 2 * simulation.hindsight_energy);
+    }
+
+    #[test]
+    fn rejects_infeasible_instances() {
+        let instance = Instance::new(jobs_from(&[(0, 1, 1), (0, 1, 1)]), 1, 1);
+        assert_eq!(
+            simulate(&instance),
+            Err(PltrError::InfeasibleInstance.to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_jobs_released_in_the_past() {
+        let mut scheduler = OnlineScheduler::new(1, 1);
+        scheduler.step().unwrap();
+        let job = Job {
+            id: 0,
+            r: 0,
+            d: 2,
+            p: 1,
+        };
+        assert!(scheduler.release(job).is_err());
+    }
+
+    #[test]
+    fn reports_missed_deadlines() {
+        let mut scheduler = OnlineScheduler::new(1, 1);
+        for id in 0..2 {
+            let job = Job {
+                id,
+                r: 0,
+                d: 1,
+                p: 1,
+            };
+            scheduler.release(job).unwrap();
+        }
+        assert!(scheduler.step().is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::energy::{busy_intervals, min_cost_matching};
+use crate::types::{Instance, ParallelSchedule, Processor};
+
+/// a state an idle processor can be put into
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SleepState {
+    pub name: String,
+    /// energy consumed in every time slot spent in this state
+    pub residual_power: usize,
+    /// energy needed to return from this state to processing
+    pub wake_up_cost: usize,
+}
+
+/// power states of a single processor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerModel {
+    /// energy consumed in every time slot in which a job is processed
+    pub active_power: usize,
+    pub sleep_states: Vec<SleepState>,
+}
+
+impl PowerModel {
+    /// the two-state model of energy: staying on at active power or turning off
+    pub fn two_state(processor: &Processor) -> PowerModel {
+        PowerModel {
+            active_power: processor.active_power,
+            sleep_states: vec![
+                SleepState {
+                    name: String::from("idle"),
+                    residual_power: processor.active_power,
+                    wake_up_cost: 0,
+                },
+                SleepState {
+                    name: String::from("off"),
+                    residual_power: 0,
+                    wake_up_cost: processor.wake_up_cost,
+                },
+            ],
+        }
+    }
+
+    /// the state with the lowest residual power, which the processor is in
+    /// before its first and after its last busy slot
+    pub fn deepest_state(&self) -> Option<usize> {
+        (0..self.sleep_states.len()).min_by_key(|&s| {
+            let state = &self.sleep_states[s];
+            (state.residual_power, state.wake_up_cost)
+        })
+    }
+
+    /// cheapest state for an idle gap of the given length and its energy
+    pub fn best_state(&self, gap: usize) -> Option<(usize, usize)> {
+        self.sleep_states
+            .iter()
+            .enumerate()
+            .map(|(s, state)| (s, state.residual_power * gap + state.wake_up_cost))
+            .min_by_key(|&(_, cost)| cost)
+    }
+}
+
+/// the sleep state chosen for an idle gap [start, end) of a processor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GapChoice {
+    pub processor: usize,
+    pub start: usize,
+    pub end: usize,
+    /// index into the sleep states of the processor
+    pub state: usize,
+}
+
+/// energy consumed by a schedule under a power-state model
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PowerReport {
+    /// energy spent processing jobs
+    pub active_energy: usize,
+    /// energy spent in sleep states during idle gaps
+    pub residual_energy: usize,
+    /// energy spent waking processors up, including the first wake-up of each processor
+    pub wake_up_energy: usize,
+    pub total: usize,
+    /// the state of each idle gap between two busy intervals of a processor
+    pub gaps: Vec<GapChoice>,
+}
+
+/// Evaluates the energy of a schedule where processor i has the power model models[i].
+/// Every idle gap between two busy intervals is spent in its cheapest sleep state.
+/// Before its first busy slot and after its last one, a processor is in its deepest state
+/// at no cost, so only its first wake-up from there is charged.
+/// Processor levels are matched to processors minimizing the total energy, as in energy.
+/// Returns None if the number of models differs from the number of processors
+/// or some processor has no sleep state although the schedule keeps a processor busy.
+pub fn power_report(
+    schedule: &ParallelSchedule,
+    instance: &Instance,
+    models: &[PowerModel],
+) -> Option<PowerReport> {
+    if models.len() != instance.m {
+        return None;
+    }
+    let busy: Vec<Vec<(usize, usize)>> = (1..instance.m + 1)
+        .map(|k| busy_intervals(schedule, k))
+        .collect();
+    let mut levels: Vec<Vec<PowerReport>> = Vec::with_capacity(instance.m);
+    for intervals in busy.iter() {
+        levels.push(
+            models
+                .iter()
+                .enumerate()
+                .map(|(i, model)| level_report(intervals, i, model))
+                .collect::<Option<Vec<PowerReport>>>()?,
+        );
+    }
+    let assignment = min_cost_matching(instance.m, |level, i| levels[level][i].total);
+    let mut report = PowerReport::default();
+    for (level, i) in assignment.into_iter().enumerate() {
+        let level_report = std::mem::take(&mut levels[level][i]);
+        report.active_energy += level_report.active_energy;
+        report.residual_energy += level_report.residual_energy;
+        report.wake_up_energy += level_report.wake_up_energy;
+        report.total += level_report.total;
+        report.gaps.extend(level_report.gaps);
+    }
+    report.gaps.sort_by_key(|gap| (gap.processor, gap.start));
+    Some(report)
+}
+
+/// energy of a processor level given by its busy intervals when it is run by processor i
+fn level_report(busy: &[(usize, usize)], i: usize, model: &PowerModel) -> Option<PowerReport> {
+    let mut report = PowerReport::default();
+    if busy.is_empty() {
+        return Some(report);
+    }
+    let deepest = model.deepest_state()?;
+    report.wake_up_energy = model.sleep_states[deepest].wake_up_cost;
+    for w in busy.windows(2) {
+        let (start, end) = (w[0].1, w[1].0);
+        let (state, _) = model.best_state(end - start)?;
+        let sleep_state = &model.sleep_states[state];
+        report.residual_energy += sleep_state.residual_power * (end - start);
+        report.wake_up_energy += sleep_state.wake_up_cost;
+        report.gaps.push(GapChoice {
+            processor: i,
+            start,
+            end,
+            state,
+        });
+    }
+    let busy_time: usize = busy.iter().map(|(a, b)| b - a).sum();
+    report.active_energy = model.active_power * busy_time;
+    report.total = report.active_energy + report.residual_energy + report.wake_up_energy;
+    Some(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::pltr;
+    use crate::energy::energy;
+    use crate::types::{jobs_from, Job, EXAMPLE_JOBS};
+
+    #[test]
+    fn two_state_model_matches_energy() {
+        let jobs = jobs_from(&EXAMPLE_JOBS[..5]);
+        for q in [1, 3, 6] {
+            let instance = Instance::new(jobs.clone(), 2, q);
+            let schedule = pltr(&instance);
+            let models: Vec<PowerModel> = instance
+                .processors
+                .iter()
+                .map(PowerModel::two_state)
+                .collect();
+            let report = power_report(&schedule, &instance, &models).unwrap();
+            assert_eq!(report.total, energy(&schedule, &instance).unwrap().total);
+        }
+    }
+
+    #[test]
+    fn chooses_state_per_gap() {
+        let schedule = ParallelSchedule::from_vec(
+            vec![
+                vec![0],
+                vec![],
+                vec![0],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![0],
+            ],
+            1,
+        );
+        let jobs = vec![Job {
+            id: 0,
+            r: 0,
+            d: 8,
+            p: 3,
+        }];
+        let instance = Instance::new(jobs, 1, 10);
+        let state = |name: &str, residual_power, wake_up_cost| SleepState {
+            name: String::from(name),
+            residual_power,
+            wake_up_cost,
+        };
+        let model = PowerModel {
+            active_power: 4,
+            sleep_states: vec![
+                state("light idle", 3, 0),
+                state("suspend", 1, 2),
+                state("off", 0, 10),
+            ],
+        };
+        let report = power_report(&schedule, &instance, &[model]).unwrap();
+        let states: Vec<usize> = report.gaps.iter().map(|gap| gap.state).collect();
+        assert_eq!(states, vec![0, 1]);
+        assert_eq!(
+            report,
+            PowerReport {
+                active_energy: 12,
+                residual_energy: 3 + 4,
+                wake_up_energy: 10 + 2,
+                total: 31,
+                gaps: report.gaps.clone(),
+            }
+        );
+    }
+}
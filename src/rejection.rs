@@ -0,0 +1,188 @@
+use crate::algorithm::{try_pltr, PltrError};
+use crate::energy::energy;
+use crate::types::{Instance, Job, ParallelSchedule};
+
+/// schedule of the accepted jobs together with the rejected ones
+#[derive(Debug, PartialEq)]
+pub struct RejectionSolution {
+    pub schedule: ParallelSchedule,
+    /// ids of the rejected jobs
+    pub rejected: Vec<usize>,
+    /// energy of the schedule plus the penalties of the rejected jobs
+    pub cost: usize,
+}
+
+/// Prize-collecting variant of pltr: jobs with a penalty in instance.penalties may be rejected,
+/// minimizing the energy of the schedule plus the penalties of the rejected jobs.
+/// Starts from accepting every job that fits into its window, rejecting jobs in order of
+/// increasing penalty per unit of processing volume while the instance is infeasible.
+/// Then single jobs are rejected or accepted again as long as that lowers the cost,
+/// each candidate set of accepted jobs being scheduled by pltr.
+/// Fails with InfeasibleInstance if the jobs that must be scheduled are infeasible on their own,
+/// and with InvalidJob if such a job does not fit into its window or a penalty is given
+/// for an id that no job has.
+pub fn pltr_with_rejection(instance: &Instance) -> Result<RejectionSolution, PltrError> {
+    if let Some(&id) = instance
+        .penalties
+        .keys()
+        .find(|&&id| instance.jobs.iter().all(|job| job.id != id))
+    {
+        return Err(PltrError::InvalidJob {
+            id,
+            reason: "penalty given for a job that is not part of the instance".to_string(),
+        });
+    }
+    let penalties: Vec<Option<usize>> = instance
+        .jobs
+        .iter()
+        .map(|job| instance.penalties.get(&job.id).copied())
+        .collect();
+    let fits = |job: &Job| job.r < job.d && job.p <= job.d - job.r;
+    let mut rejected: Vec<bool> = instance.jobs.iter().map(|job| !fits(job)).collect();
+    if let Some(j) = (0..instance.jobs.len()).find(|&j| rejected[j] && penalties[j].is_none()) {
+        return Err(PltrError::InvalidJob {
+            id: instance.jobs[j].id,
+            reason: "does not fit into its window and may not be rejected".to_string(),
+        });
+    }
+    let mut optional: Vec<usize> = (0..instance.jobs.len())
+        .filter(|&j| penalties[j].is_some())
+        .collect();
+    // increasing penalty / p, compared without division
+    optional.sort_by(|&a, &b| {
+        let penalty = |j: usize| penalties[j].unwrap_or(0);
+        (penalty(a) * instance.jobs[b].p).cmp(&(penalty(b) * instance.jobs[a].p))
+    });
+    let accepted: Vec<usize> = optional.iter().copied().filter(|&j| !rejected[j]).collect();
+    let mut candidates = accepted.into_iter();
+    let mut best = loop {
+        match evaluate(instance, &penalties, &rejected) {
+            Ok(solution) => break solution,
+            Err(PltrError::InfeasibleInstance) => {
+                let j = candidates.next().ok_or(PltrError::InfeasibleInstance)?;
+                rejected[j] = true;
+            }
+            Err(error) => return Err(error),
+        }
+    };
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for &j in optional.iter().filter(|&&j| fits(&instance.jobs[j])) {
+            rejected[j] = !rejected[j];
+            match evaluate(instance, &penalties, &rejected) {
+                Ok(solution) if solution.cost < best.cost => {
+                    best = solution;
+                    improved = true;
+                }
+                _ => rejected[j] = !rejected[j],
+            }
+        }
+    }
+    Ok(best)
+}
+
+/// schedules the accepted jobs by pltr and adds the penalties of the rejected ones
+fn evaluate(
+    instance: &Instance,
+    penalties: &[Option<usize>],
+    rejected: &[bool],
+) -> Result<RejectionSolution, PltrError> {
+    let mut accepted_jobs: Vec<Job> = Vec::new();
+    let mut rejected_jobs: Vec<&Job> = Vec::new();
+    let mut rejected_penalties = 0;
+    for (j, job) in instance.jobs.iter().enumerate() {
+        if rejected[j] {
+            rejected_jobs.push(job);
+            rejected_penalties += penalties[j].unwrap_or(0);
+        } else {
+            accepted_jobs.push(job.clone());
+        }
+    }
+    let accepted = Instance::with_processors(accepted_jobs, instance.processors.clone());
+    let schedule = try_pltr(&accepted)?;
+    let cost = energy(&schedule, &accepted)
+        .map_err(PltrError::InvariantViolation)?
+        .total
+        + rejected_penalties;
+    let mut slots = schedule.slots().to_vec();
+    slots.resize(instance.d_max, Vec::new());
+    Ok(RejectionSolution {
+        schedule: ParallelSchedule::from_vec(slots, instance.m),
+        rejected: rejected_jobs.iter().map(|job| job.id).collect(),
+        cost,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn instance(m: usize) -> Instance {
+        let jobs = vec![
+            Job {
+                id: 0,
+                r: 0,
+                d: 2,
+                p: 2,
+            },
+            Job {
+                id: 1,
+                r: 10,
+                d: 11,
+                p: 1,
+            },
+            Job {
+                id: 2,
+                r: 0,
+                d: 2,
+                p: 2,
+            },
+        ];
+        Instance::new(jobs, m, 5)
+    }
+
+    fn penalties(penalty: usize) -> BTreeMap<usize, usize> {
+        BTreeMap::from([(1, penalty), (2, penalty)])
+    }
+
+    #[test]
+    fn rejects_expensive_jobs() {
+        let solution = pltr_with_rejection(&instance(2).with_penalties(penalties(3))).unwrap();
+        assert_eq!(solution.rejected, vec![1, 2]);
+        assert_eq!(solution.cost, 2 + 5 + 3 + 3);
+        let solution = pltr_with_rejection(&instance(2).with_penalties(penalties(20))).unwrap();
+        assert!(solution.rejected.is_empty());
+        assert_eq!(solution.cost, (2 + 5 + 1 + 5) + (2 + 5));
+        assert!(solution.schedule.is_valid_for(&instance(2)).is_ok());
+    }
+
+    #[test]
+    fn rejects_to_restore_feasibility() {
+        let solution = pltr_with_rejection(&instance(1).with_penalties(penalties(20))).unwrap();
+        assert_eq!(solution.rejected, vec![2]);
+        assert_eq!(solution.cost, (2 + 5 + 1 + 5) + 20);
+    }
+
+    #[test]
+    fn reports_invalid_penalties_and_jobs() {
+        let unknown = BTreeMap::from([(7, 1)]);
+        assert!(matches!(
+            pltr_with_rejection(&instance(2).with_penalties(unknown)),
+            Err(PltrError::InvalidJob { id: 7, .. })
+        ));
+        let mut too_long = instance(2);
+        too_long.jobs[0].p = 3;
+        assert!(matches!(
+            pltr_with_rejection(&too_long),
+            Err(PltrError::InvalidJob { id: 0, .. })
+        ));
+        let jobs = instance(1).jobs;
+        let crowded = Instance::new(vec![jobs[0].clone(), jobs[2].clone()], 1, 5);
+        assert_eq!(
+            pltr_with_rejection(&crowded),
+            Err(PltrError::InfeasibleInstance)
+        );
+    }
+}
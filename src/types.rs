@@ -1,8 +1,11 @@
-use pathfinding::prelude::{DenseCapacity, EdmondsKarp};
+use crate::flow::FlowNetwork;
+use crate::json::{InstanceDocument, ScheduleDocument};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Write;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Job {
     pub id: usize, //identifier
     pub r: usize,  //release time
@@ -26,9 +29,36 @@ impl Job {
 }
 
 /// single-processor schedule
+#[derive(Clone, PartialEq, Debug)]
 pub struct Schedule(pub Vec<Option<usize>>);
+impl From<Schedule> for ParallelSchedule {
+    /// the schedule on a single processor as a parallel schedule with m = 1
+    fn from(schedule: Schedule) -> ParallelSchedule {
+        let slots = schedule
+            .0
+            .into_iter()
+            .map(|job| job.into_iter().collect())
+            .collect();
+        ParallelSchedule(slots, 1)
+    }
+}
+impl TryFrom<&ParallelSchedule> for Schedule {
+    type Error = String;
+    /// fails if more than one job is scheduled in some time slot
+    fn try_from(schedule: &ParallelSchedule) -> Result<Schedule, String> {
+        let mut single: Vec<Option<usize>> = Vec::with_capacity(schedule.0.len());
+        for (t, jobs) in schedule.0.iter().enumerate() {
+            if jobs.len() > 1 {
+                return Err(format!("{} jobs scheduled in time slot {}", jobs.len(), t));
+            }
+            single.push(jobs.first().copied());
+        }
+        Ok(Schedule(single))
+    }
+}
 /// multi-processor schedule
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(into = "ScheduleDocument", try_from = "ScheduleDocument")]
 pub struct ParallelSchedule(Vec<Vec<usize>>, usize);
 impl ParallelSchedule {
     fn timeslots_of(&self, job: &Job) -> Vec<usize> {
@@ -43,6 +73,15 @@ impl ParallelSchedule {
         timeslots
     }
     pub fn is_valid_for(&self, instance: &Instance) -> Result<(), String> {
+        let m = self.1.min(instance.m);
+        if let Some(t) = self.0.iter().position(|jobs| jobs.len() > m) {
+            return Err(format!(
+                "{} jobs scheduled in time slot {} on {} processors",
+                self.0[t].len(),
+                t,
+                m
+            ));
+        }
         for job in instance.jobs.iter() {
             let slots: Vec<usize> = self.timeslots_of(job);
             if slots.len() != job.p {
@@ -61,43 +100,198 @@ impl ParallelSchedule {
         }
         Ok(())
     }
-    pub fn from_flow(nw: &DenseCapacity<i32>, instance: &Instance) -> ParallelSchedule {
+    /// checks validity as is_valid_for and additionally that every job is processed
+    /// in consecutive time slots, so it can run without preemption on a single processor
+    pub fn is_valid_non_preemptive_for(&self, instance: &Instance) -> Result<(), String> {
+        self.is_valid_for(instance)?;
+        for job in instance.jobs.iter() {
+            let slots: Vec<usize> = self.timeslots_of(job);
+            if slots.windows(2).any(|w| w[1] != w[0] + 1) {
+                return Err(format!(
+                    "job {} is preempted: scheduled in slots {:?}",
+                    job.id, slots
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// extracts the schedule from a maximum flow in the network of pltr
+    /// panics if the resulting schedule is invalid
+    pub fn from_flow<N: FlowNetwork>(nw: &N, instance: &Instance) -> ParallelSchedule {
+        match ParallelSchedule::try_from_flow(nw, instance) {
+            Ok(schedule) => schedule,
+            Err(message) => panic!("{}", message),
+        }
+    }
+    /// extracts the schedule from a maximum flow in the network of pltr
+    pub fn try_from_flow<N: FlowNetwork>(
+        nw: &N,
+        instance: &Instance,
+    ) -> Result<ParallelSchedule, String> {
         let mut schedule: ParallelSchedule =
             ParallelSchedule(vec![Vec::new(); instance.d_max], instance.m);
-        for t in 0..instance.d_max {
-            for (j, job) in instance.jobs.iter().enumerate() {
+        for (j, job) in instance.jobs.iter().enumerate() {
+            let u_j = instance.u_node(j);
+            for t in job.r..job.d {
                 let v_t = instance.v_node(t);
-                let u_j = instance.u_node(j);
                 if nw.flow(u_j, v_t) == 1 {
                     schedule.0[t].push(job.id);
                 }
             }
         }
-        let validation_result = schedule.is_valid_for(instance);
-        assert_eq!(
-            validation_result,
-            Ok(()),
-            "\nThe invalid schedule:\n{}",
-            schedule
-        );
-        schedule
+        match schedule.is_valid_for(instance) {
+            Ok(()) => Ok(schedule),
+            Err(message) => Err(format!("{}\nThe invalid schedule:\n{}", message, schedule)),
+        }
     }
     pub fn from_vec(vec: Vec<Vec<usize>>, m: usize) -> ParallelSchedule {
         ParallelSchedule(vec, m)
     }
+    /// returns the ids of the jobs scheduled in each time slot
+    pub fn slots(&self) -> &[Vec<usize>] {
+        &self.0
+    }
+    /// returns the number of processors the schedule is defined for
+    pub fn m(&self) -> usize {
+        self.1
+    }
+}
+
+/// power characteristics of a single processor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Processor {
+    /// energy needed to turn the processor on
+    pub wake_up_cost: usize,
+    /// energy consumed in every time slot the processor is on
+    pub active_power: usize,
+}
+impl Processor {
+    /// a processor of the homogeneous model with wake-up cost q and unit active power
+    pub fn homogeneous(q: usize) -> Processor {
+        Processor {
+            wake_up_cost: q,
+            active_power: 1,
+        }
+    }
+    /// length from which on an idle gap is cheaper to spend turned off than on
+    pub fn bridging_threshold(&self) -> usize {
+        if self.active_power == 0 {
+            return usize::MAX;
+        }
+        self.wake_up_cost.div_ceil(self.active_power)
+    }
 }
 
 /// all data specifiying a problem instance,
 /// in addition to precomputed maximum deadline (d_max) and total processing volume across all jobs (p_total)
-#[derive(Clone, Debug)]
+/// q is the smallest wake-up cost among the processors
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "InstanceDocument", try_from = "InstanceDocument")]
 pub struct Instance {
     pub jobs: Vec<Job>,
     pub m: usize,
     pub q: usize,
+    pub processors: Vec<Processor>,
     pub d_max: usize,
     pub p_total: usize,
+    /// penalty for rejecting the job with the given id, jobs without one must be scheduled
+    pub penalties: BTreeMap<usize, usize>,
 }
 
+/// job on unrelated machines: processing volume p[i] on machine i,
+/// None if the job may not run on machine i
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnrelatedJob {
+    pub id: usize,
+    pub r: usize,
+    pub d: usize,
+    pub p: Vec<Option<usize>>,
+}
+
+/// problem instance on unrelated machines with common wake-up cost q;
+/// every job has to be processed entirely on a single machine
+#[derive(Clone, Debug)]
+pub struct UnrelatedInstance {
+    pub jobs: Vec<UnrelatedJob>,
+    pub m: usize,
+    pub q: usize,
+    pub d_max: usize,
+}
+
+impl UnrelatedInstance {
+    pub fn new(jobs: Vec<UnrelatedJob>, m: usize, q: usize) -> UnrelatedInstance {
+        let d_max = jobs.iter().map(|job| job.d).max().unwrap_or(0);
+        UnrelatedInstance { jobs, m, q, d_max }
+    }
+    /// the single-processor instance of the given jobs on machine i,
+    /// None if one of the jobs is ineligible for machine i
+    pub fn machine_instance(&self, i: usize, jobs: &[usize]) -> Option<Instance> {
+        let machine_jobs = jobs
+            .iter()
+            .map(|&j| {
+                let job = &self.jobs[j];
+                job.p[i].map(|p| Job {
+                    id: job.id,
+                    r: job.r,
+                    d: job.d,
+                    p,
+                })
+            })
+            .collect::<Option<Vec<Job>>>()?;
+        Some(Instance::new(machine_jobs, 1, self.q))
+    }
+}
+
+/// schedule on unrelated machines: the timeline of each machine
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnrelatedSchedule(pub Vec<Schedule>);
+
+impl UnrelatedSchedule {
+    pub fn is_valid_for(&self, instance: &UnrelatedInstance) -> Result<(), String> {
+        if self.0.len() != instance.m {
+            return Err(format!(
+                "schedule for {} machines, but instance has {}",
+                self.0.len(),
+                instance.m
+            ));
+        }
+        for job in instance.jobs.iter() {
+            let mut machines = self.0.iter().enumerate().filter_map(|(i, timeline)| {
+                let slots: Vec<usize> = (0..timeline.0.len())
+                    .filter(|&t| timeline.0[t] == Some(job.id))
+                    .collect();
+                (!slots.is_empty()).then_some((i, slots))
+            });
+            let Some((i, slots)) = machines.next() else {
+                return Err(format!("job {} not scheduled", job.id));
+            };
+            if machines.next().is_some() {
+                return Err(format!("job {} scheduled on several machines", job.id));
+            }
+            let Some(p) = job.p[i] else {
+                return Err(format!("job {} scheduled on ineligible machine {}", job.id, i));
+            };
+            if slots.len() != p {
+                return Err(format!(
+                    "job {} not feasibly scheduled: for {} of {} units scheduled on machine {}",
+                    job.id,
+                    slots.len(),
+                    p,
+                    i
+                ));
+            }
+            if slots.iter().any(|&t| t < job.r || t >= job.d) {
+                return Err(format!("job {} not feasibly scheduled", job.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// the smallest wake-up cost among the processors, which is q of an instance
+pub(crate) fn min_wake_up_cost(processors: &[Processor]) -> usize {
+    processors.iter().map(|p| p.wake_up_cost).min().unwrap_or(0)
+}
 
 fn get_d_max(jobs: &[Job]) -> usize {
     let mut d_max: usize = 0;
@@ -118,17 +312,41 @@ fn get_total_processing_volume(jobs: &[Job]) -> usize {
 }
 
 impl Instance {
+    /// instance on m identical processors with wake-up cost q and unit active power
     pub fn new(jobs: Vec<Job>, m: usize, q: usize) -> Instance {
+        Instance::with_processors(jobs, vec![Processor::homogeneous(q); m])
+    }
+    /// instance on heterogeneous processors
+    pub fn with_processors(jobs: Vec<Job>, processors: Vec<Processor>) -> Instance {
         let d_max = get_d_max(&jobs);
         let p_total = get_total_processing_volume(&jobs);
         Instance {
             jobs,
-            m,
-            q,
+            m: processors.len(),
+            q: min_wake_up_cost(&processors),
+            processors,
             d_max,
             p_total,
+            penalties: BTreeMap::new(),
         }
     }
+    /// the instance in which the jobs with the given ids may be rejected for their penalty
+    pub fn with_penalties(mut self, penalties: BTreeMap<usize, usize>) -> Instance {
+        self.penalties = penalties;
+        self
+    }
+    /// Returns the processor indices in the order in which pltr opens them:
+    /// pltr keeps processor k busy only where fewer than k processors cannot do the work,
+    /// so the processor with the lowest active power, and then the lowest wake-up cost,
+    /// is used at k = 1 and the most expensive one at k = m.
+    pub fn processor_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.processors.len()).collect();
+        order.sort_by_key(|&i| {
+            let processor = &self.processors[i];
+            (processor.active_power, processor.wake_up_cost)
+        });
+        order
+    }
     /// returns (the number of) the node u_j in the maximum flow network corresponding to job j
     pub fn u_node(&self, job: usize) -> usize {
         1 + job
@@ -268,3 +486,30 @@ impl fmt::Display for Instance {
         Ok(())
     }
 }
+
+/// (r, d, p) of jobs that need up to three processors and leave idle gaps of different lengths
+#[cfg(test)]
+pub(crate) const EXAMPLE_JOBS: [(usize, usize, usize); 8] = [
+    (0, 3, 1),
+    (1, 9, 2),
+    (6, 7, 1),
+    (7, 9, 2),
+    (12, 20, 3),
+    (0, 4, 4),
+    (2, 6, 3),
+    (14, 16, 2),
+];
+
+/// jobs with ids 0, 1, ... and the given (r, d, p), for tests
+#[cfg(test)]
+pub(crate) fn jobs_from(jobs: &[(usize, usize, usize)]) -> Vec<Job> {
+    jobs.iter()
+        .enumerate()
+        .map(|(id, &(r, d, p))| Job {
+            id,
+            r,
+            d,
+            p,
+        })
+        .collect()
+}
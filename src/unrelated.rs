@@ -0,0 +1,164 @@
+use crate::energy::{energy, EnergyCost};
+use crate::ltr::try_ltr;
+use crate::types::{Instance, ParallelSchedule, Schedule, UnrelatedInstance, UnrelatedSchedule};
+
+/// Heuristic for unrelated machines: every job is assigned to one eligible machine,
+/// and each machine is scheduled by the classic Left-to-Right algorithm, a 2-approximation.
+/// Jobs with few eligible machines and early deadlines are assigned first, each to the
+/// machine whose Left-to-Right schedule remains feasible and gains the least energy,
+/// preferring the machine with the smaller processing volume on ties.
+/// The energy of Left-to-Right is not monotone in the jobs, as an additional job may let it
+/// bridge a gap more cheaply, so a machine may also lose energy.
+/// Returns None if some job fits on none of its eligible machines, which does not imply
+/// that the instance is infeasible.
+pub fn unrelated(instance: &UnrelatedInstance) -> Option<UnrelatedSchedule> {
+    let mut order: Vec<usize> = (0..instance.jobs.len()).collect();
+    order.sort_by_key(|&j| {
+        let job = &instance.jobs[j];
+        (job.p.iter().flatten().count(), job.d)
+    });
+    let mut assigned: Vec<Vec<usize>> = vec![Vec::new(); instance.m];
+    let mut costs: Vec<usize> = vec![0; instance.m];
+    let mut timelines: Vec<Schedule> = vec![Schedule(Vec::new()); instance.m];
+    for j in order {
+        let mut best: Option<(i64, usize, usize, usize, Schedule)> = None;
+        for i in 0..instance.m {
+            let Some(p) = instance.jobs[j].p[i] else {
+                continue;
+            };
+            let mut jobs = assigned[i].clone();
+            jobs.push(j);
+            let Some((cost, schedule)) = schedule_machine(instance, i, &jobs) else {
+                continue;
+            };
+            let increase = cost as i64 - costs[i] as i64;
+            if best
+                .as_ref()
+                .is_none_or(|(best_increase, best_p, _, _, _)| {
+                    (increase, p) < (*best_increase, *best_p)
+                })
+            {
+                best = Some((increase, p, i, cost, schedule));
+            }
+        }
+        let (_, _, i, cost, schedule) = best?;
+        assigned[i].push(j);
+        costs[i] = cost;
+        timelines[i] = schedule;
+    }
+    for timeline in timelines.iter_mut() {
+        timeline.0.resize(instance.d_max, None);
+    }
+    Some(UnrelatedSchedule(timelines))
+}
+
+/// schedules the jobs on machine i by Left-to-Right and returns its energy with the schedule,
+/// None if they cannot be feasibly scheduled there
+fn schedule_machine(
+    instance: &UnrelatedInstance,
+    i: usize,
+    jobs: &[usize],
+) -> Option<(usize, Schedule)> {
+    let machine_instance = instance.machine_instance(i, jobs)?;
+    let schedule = try_ltr(&machine_instance).ok()?;
+    let cost = machine_energy(&schedule, &machine_instance).total;
+    Some((cost, schedule))
+}
+
+fn machine_energy(schedule: &Schedule, machine_instance: &Instance) -> EnergyCost {
+    energy(&ParallelSchedule::from(schedule.clone()), machine_instance)
+        .expect("a single machine runs at most one job per slot")
+}
+
+/// energy of a schedule on unrelated machines, summed over the machines
+pub fn unrelated_energy(schedule: &UnrelatedSchedule, instance: &UnrelatedInstance) -> EnergyCost {
+    let mut cost = EnergyCost::default();
+    for timeline in schedule.0.iter() {
+        let machine_instance = Instance::new(Vec::new(), 1, instance.q);
+        let machine_cost = machine_energy(timeline, &machine_instance);
+        cost.busy_time += machine_cost.busy_time;
+        cost.bridged_idle_time += machine_cost.bridged_idle_time;
+        cost.wakeups += machine_cost.wakeups;
+        cost.total += machine_cost.total;
+    }
+    cost
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::UnrelatedJob;
+
+    #[test]
+    fn respects_eligibility_and_volumes() {
+        let jobs = vec![
+            UnrelatedJob {
+                id: 0,
+                r: 0,
+                d: 4,
+                p: vec![Some(2), None],
+            },
+            UnrelatedJob {
+                id: 1,
+                r: 0,
+                d: 4,
+                p: vec![Some(4), Some(2)],
+            },
+            UnrelatedJob {
+                id: 2,
+                r: 2,
+                d: 6,
+                p: vec![Some(2), Some(3)],
+            },
+        ];
+        let instance = UnrelatedInstance::new(jobs, 2, 1);
+        let schedule = unrelated(&instance).unwrap();
+        assert!(schedule.is_valid_for(&instance).is_ok());
+        assert!(schedule.0[1].0.iter().all(|job| *job != Some(0)));
+        assert_eq!(unrelated_energy(&schedule, &instance).busy_time, 6);
+    }
+
+    #[test]
+    fn energy_may_decrease_with_a_job() {
+        // the third job lets Left-to-Right bridge the gap after the second one
+        let jobs = [(1, 6, 2), (0, 1, 1), (2, 5, 1)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(r, d, p))| UnrelatedJob {
+                id,
+                r,
+                d,
+                p: vec![Some(p)],
+            })
+            .collect();
+        let instance = UnrelatedInstance::new(jobs, 1, 6);
+        let schedule = unrelated(&instance).unwrap();
+        assert!(schedule.is_valid_for(&instance).is_ok());
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let jobs = vec![UnrelatedJob {
+            id: 0,
+            r: 0,
+            d: 2,
+            p: vec![None, Some(1)],
+        }];
+        let instance = UnrelatedInstance::new(jobs, 2, 1);
+        let ineligible = UnrelatedSchedule(vec![
+            Schedule(vec![Some(0), None]),
+            Schedule(vec![None, None]),
+        ]);
+        assert!(ineligible.is_valid_for(&instance).is_err());
+        let split = UnrelatedSchedule(vec![
+            Schedule(vec![Some(0), None]),
+            Schedule(vec![None, Some(0)]),
+        ]);
+        assert!(split.is_valid_for(&instance).is_err());
+        let valid = UnrelatedSchedule(vec![
+            Schedule(vec![None, None]),
+            Schedule(vec![None, Some(0)]),
+        ]);
+        assert!(valid.is_valid_for(&instance).is_ok());
+    }
+}
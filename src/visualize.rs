@@ -0,0 +1,165 @@
+use crate::flow::FlowNetwork;
+use crate::types::Instance;
+use graphviz_rust::dot_structures::{
+    Attribute, Edge, EdgeTy, Graph, Id, Node, NodeId, Stmt, Vertex,
+};
+use graphviz_rust::printer::{DotPrinter, PrinterContext};
+use std::collections::VecDeque;
+
+/// Renders the network of create_graph for the instance as a DOT graph.
+/// Every edge with positive capacity is labelled with its flow and capacity.
+/// The nodes reachable from alpha in the residual network are filled, and the edges leaving
+/// them form a minimum cut once the flow is maximal and are drawn in red.
+/// Can be called on the network at any point of pltr, e.g. while probing in keepidle and
+/// keepbusy, where it is logged at trace level with target pltr::network.
+pub fn visualize<N: FlowNetwork>(nw: &N, instance: &Instance) -> String {
+    let edges = edges(nw, instance);
+    let source_side = reachable_from_alpha(nw, instance, &edges);
+    let mut stmts: Vec<Stmt> = Vec::new();
+    stmts.push(Stmt::Attribute(attribute("rankdir", "LR")));
+    for (node, &is_source_side) in source_side.iter().enumerate() {
+        let mut attributes = vec![attribute("label", &label(instance, node))];
+        if is_source_side {
+            attributes.push(attribute("style", "filled"));
+            attributes.push(attribute("fillcolor", "lightblue"));
+        }
+        stmts.push(Stmt::Node(Node::new(node_id(node), attributes)));
+    }
+    for &(from, to) in edges.iter() {
+        let label = format!("{}/{}", nw.flow(from, to), nw.capacity(from, to));
+        let mut attributes = vec![attribute("label", &label)];
+        if source_side[from] && !source_side[to] {
+            attributes.push(attribute("color", "red"));
+            attributes.push(attribute("penwidth", "2"));
+        }
+        stmts.push(Stmt::Edge(Edge {
+            ty: EdgeTy::Pair(Vertex::N(node_id(from)), Vertex::N(node_id(to))),
+            attributes,
+        }));
+    }
+    let graph = Graph::DiGraph {
+        id: Id::Plain("pltr".to_string()),
+        strict: false,
+        stmts,
+    };
+    graph.print(&mut PrinterContext::default())
+}
+
+/// edges of the network of create_graph with positive capacity
+fn edges<N: FlowNetwork>(nw: &N, instance: &Instance) -> Vec<(usize, usize)> {
+    let alpha = instance.alpha_node();
+    let gamma = instance.gamma_node();
+    let omega = instance.omega_node();
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (j, job) in instance.jobs.iter().enumerate() {
+        edges.push((alpha, instance.u_node(j)));
+        for t in job.r..job.d {
+            edges.push((instance.u_node(j), instance.v_node(t)));
+        }
+    }
+    for t in 0..instance.d_max {
+        edges.push((instance.v_node(t), gamma));
+        edges.push((instance.v_node(t), omega));
+    }
+    edges.push((gamma, omega));
+    edges.retain(|&(from, to)| nw.capacity(from, to) > 0);
+    edges
+}
+
+/// whether each node is reachable from alpha in the residual network
+fn reachable_from_alpha<N: FlowNetwork>(
+    nw: &N,
+    instance: &Instance,
+    edges: &[(usize, usize)],
+) -> Vec<bool> {
+    let mut reachable = vec![false; instance.omega_node() + 1];
+    reachable[instance.alpha_node()] = true;
+    let mut queue: VecDeque<usize> = VecDeque::from([instance.alpha_node()]);
+    while let Some(node) = queue.pop_front() {
+        for &(from, to) in edges.iter() {
+            let next = if from == node && nw.residual_capacity(from, to) > 0 {
+                to
+            } else if to == node && nw.flow(from, to) > 0 {
+                from
+            } else {
+                continue;
+            };
+            if !reachable[next] {
+                reachable[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    reachable
+}
+
+fn label(instance: &Instance, node: usize) -> String {
+    if node == instance.alpha_node() {
+        "alpha".to_string()
+    } else if node == instance.gamma_node() {
+        "gamma".to_string()
+    } else if node == instance.omega_node() {
+        "omega".to_string()
+    } else if node < instance.v_node(0) {
+        let job = &instance.jobs[node - instance.u_node(0)];
+        format!("u_{} (job {})", node - instance.u_node(0), job.id)
+    } else {
+        format!("v_{}", node - instance.v_node(0))
+    }
+}
+
+fn node_id(node: usize) -> NodeId {
+    NodeId(Id::Plain(node.to_string()), None)
+}
+
+fn attribute(key: &str, value: &str) -> Attribute {
+    Attribute(
+        Id::Plain(key.to_string()),
+        Id::Escaped(format!("\"{value}\"")),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::create_graph;
+    use crate::flow::EdmondsKarp;
+    use crate::types::Job;
+
+    #[test]
+    fn highlights_minimum_cut() {
+        // both jobs compete for slot 0 on a single processor
+        let jobs = vec![
+            Job {
+                id: 0,
+                r: 0,
+                d: 1,
+                p: 1,
+            },
+            Job {
+                id: 1,
+                r: 0,
+                d: 2,
+                p: 2,
+            },
+        ];
+        let instance = Instance::new(jobs, 1, 1);
+        let mut nw: EdmondsKarp = create_graph(&instance);
+        assert_eq!(nw.augment(), 2);
+        let dot = visualize(&nw, &instance);
+        assert!(dot.starts_with("digraph pltr {"));
+        for label in [
+            "alpha",
+            "u_0 (job 0)",
+            "u_1 (job 1)",
+            "v_0",
+            "v_1",
+            "gamma",
+            "omega",
+        ] {
+            assert!(dot.contains(&format!("label=\"{label}\"")));
+        }
+        // the minimum cut has capacity 2 and consists of two saturated edges of capacity 1
+        assert_eq!(dot.matches("label=\"1/1\",color=\"red\"").count(), 2);
+    }
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const INSTANCE: &str = r#"{"version": 1, "m": 1, "q": 3, "jobs": [
+    {"id": 2, "r": 0, "d": 2, "p": 1},
+    {"id": 1, "r": 3, "d": 5, "p": 1},
+    {"id": 0, "r": 2, "d": 6, "p": 1}
+]}"#;
+
+/// runs the validate subcommand on INSTANCE and the schedule document
+fn validate(name: &str, schedule: &str) -> Output {
+    let dir = std::env::temp_dir();
+    let file = |suffix: &str| -> PathBuf {
+        dir.join(format!(
+            "pltr-cli-{}-{name}-{suffix}.json",
+            std::process::id()
+        ))
+    };
+    let (instance_path, schedule_path) = (file("instance"), file("schedule"));
+    fs::write(&instance_path, INSTANCE).unwrap();
+    fs::write(&schedule_path, schedule).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_pltr"))
+        .arg("validate")
+        .arg(&instance_path)
+        .arg(&schedule_path)
+        .output()
+        .unwrap();
+    fs::remove_file(instance_path).unwrap();
+    fs::remove_file(schedule_path).unwrap();
+    output
+}
+
+#[test]
+fn validate_reports_overfull_slots_as_invalid() {
+    for (name, schedule) in [
+        // more jobs in a slot than the instance has processors
+        (
+            "instance-m",
+            r#"{"version": 1, "m": 3, "slots": [[2, 0], [], [], [1]]}"#,
+        ),
+        // more jobs in a slot than the document itself allows
+        (
+            "document-m",
+            r#"{"version": 1, "m": 1, "slots": [[0, 1, 2], [], []]}"#,
+        ),
+    ] {
+        let output = validate(name, schedule);
+        assert_eq!(output.status.code(), Some(1), "{name}");
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("invalid"));
+    }
+}
+
+#[test]
+fn validate_exit_codes() {
+    let valid = validate(
+        "valid",
+        r#"{"version": 1, "m": 1, "slots": [[2], [], [0], [1]]}"#,
+    );
+    assert_eq!(valid.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&valid.stdout).starts_with("valid"));
+    let malformed = validate("malformed", r#"{"version": 1, "m": 1}"#);
+    assert_eq!(malformed.status.code(), Some(2));
+    let other_version = validate("version", r#"{"version": 2, "m": 1, "slots": []}"#);
+    assert_eq!(other_version.status.code(), Some(2));
+}